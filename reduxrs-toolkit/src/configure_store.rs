@@ -0,0 +1,106 @@
+use std::rc::Rc;
+
+use reduxrs::core::enhancer::{
+    self, StoreEnhancer, apply_middleware, compose_enhancers, create_store,
+};
+use reduxrs::core::middleware::Middleware;
+use reduxrs::core::store::{Reducer, Store};
+use reduxrs::core::timer::Timer;
+
+/// 等价 configureStore 的 options：reducer + 默认 state，其余按需链式设置
+pub struct ConfigureStore<S, A> {
+    pub(crate) reducer: Box<Reducer<S, A>>,
+    // reducer 的默认 state（Rust 的 reducer 没有 undefined 初始化这一步）
+    pub(crate) initial_state: S,
+    pub(crate) preloaded_state: Option<S>,
+    middleware: Vec<Box<dyn Middleware<S, A>>>,
+    enhancers: Vec<Box<dyn StoreEnhancer<S, A>>>,
+    timer: Option<Rc<dyn Timer>>,
+}
+
+pub fn configure_store<S: 'static, A: 'static>(
+    reducer: impl Fn(&S, &A) -> S + 'static,
+    initial_state: S,
+) -> ConfigureStore<S, A> {
+    ConfigureStore {
+        reducer: Box::new(reducer),
+        initial_state,
+        preloaded_state: None,
+        middleware: Vec::new(),
+        enhancers: Vec::new(),
+        timer: None,
+    }
+}
+
+impl<S: 'static, A: 'static> ConfigureStore<S, A> {
+    /// 覆盖默认 state
+    pub fn preloaded_state(mut self, state: S) -> Self {
+        self.preloaded_state = Some(state);
+        self
+    }
+
+    /// 按调用顺序排列，先加的先看到 action
+    pub fn middleware(mut self, middleware: impl Middleware<S, A> + 'static) -> Self {
+        self.middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// 按调用顺序排列，都在 middleware enhancer 之内
+    pub fn enhancer(mut self, enhancer: impl StoreEnhancer<S, A> + 'static) -> Self {
+        self.enhancers.push(Box::new(enhancer));
+        self
+    }
+
+    /// store 的定时器（见 Store::set_timer）；测试里传 ManualTimer
+    pub fn timer(mut self, timer: Rc<dyn Timer>) -> Self {
+        self.timer = Some(timer);
+        self
+    }
+
+    pub fn build(self) -> Store<S, A> {
+        let mut enhancers: Vec<Box<dyn StoreEnhancer<S, A>>> =
+            vec![Box::new(apply_middleware(self.middleware))];
+        enhancers.extend(self.enhancers);
+        // 最内层：其余 enhancer 装配时（例如 Timeout 的 auto_batch）已经有定时器
+        if let Some(timer) = self.timer {
+            enhancers.push(Box::new(enhancer::timer(timer)));
+        }
+        create_store(
+            self.reducer,
+            self.preloaded_state.unwrap_or(self.initial_state),
+            compose_enhancers(enhancers),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reduxrs::core::autobatch::BatchSchedule;
+    use reduxrs::core::enhancer::auto_batch;
+    use reduxrs::core::timer::ManualTimer;
+
+    use super::*;
+
+    #[test]
+    fn timer_is_available_to_enhancers() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = configure_store(|state: &i32, action: &i32| state + action, 0)
+            .timer(timer.clone())
+            .enhancer(auto_batch(
+                |_: &i32| true,
+                BatchSchedule::Timeout(Duration::from_millis(5)),
+            ))
+            .build();
+        let notified = Rc::new(std::cell::Cell::new(0));
+        let count = notified.clone();
+        let _sub = store.subscribe(move |_, _| count.set(count.get() + 1));
+        store.dispatch(1);
+        store.dispatch(2);
+        assert_eq!(notified.get(), 0);
+        timer.advance(Duration::from_millis(5));
+        assert_eq!(notified.get(), 1);
+        assert_eq!(store.get_state(), 3);
+    }
+}
@@ -0,0 +1,206 @@
+use std::any::Any;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reduxrs::core::fsa::FsaAction;
+use reduxrs::core::storet::Action;
+
+use crate::nanoid::nanoid;
+
+/// Flux Standard Action 形状的 action：{ type, payload, meta, error }
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayloadAction<P, M = ()> {
+    pub type_: &'static str,
+    pub payload: P,
+    pub meta: M,
+    pub error: bool,
+}
+
+impl<P, M: 'static> Action for PayloadAction<P, M> {
+    fn type_(&self) -> &str {
+        self.type_
+    }
+
+    fn meta(&self) -> Option<&dyn Any> {
+        Some(&self.meta)
+    }
+
+    fn is_error(&self) -> bool {
+        self.error
+    }
+
+    /// meta 为 RequestMeta 时用 request_id（每次创建都不同）
+    fn id(&self) -> Option<&str> {
+        let meta: &dyn Any = &self.meta;
+        meta.downcast_ref::<RequestMeta>()
+            .map(|meta| meta.request_id.as_str())
+    }
+}
+
+/// prepare 回调的返回值（等价 RTK prepare 返回的 { payload, meta?, error? }）
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prepared<P, M = ()> {
+    pub payload: P,
+    pub meta: M,
+    pub error: bool,
+}
+
+impl<P> Prepared<P> {
+    pub fn new(payload: P) -> Self {
+        Self {
+            payload,
+            meta: (),
+            error: false,
+        }
+    }
+}
+
+impl<P, M> Prepared<P, M> {
+    pub fn with_meta<N>(self, meta: N) -> Prepared<P, N> {
+        Prepared {
+            payload: self.payload,
+            meta,
+            error: self.error,
+        }
+    }
+
+    pub fn error(mut self) -> Self {
+        self.error = true;
+        self
+    }
+}
+
+/// 常用的 meta：自动生成的 request id + 毫秒时间戳
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestMeta {
+    pub request_id: String,
+    pub timestamp: u64,
+}
+
+impl RequestMeta {
+    pub fn new() -> Self {
+        Self {
+            request_id: nanoid(),
+            timestamp: now_millis(),
+        }
+    }
+}
+
+impl Default for RequestMeta {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+pub type Prepare<Args, P, M> = dyn Fn(Args) -> Prepared<P, M> + 'static;
+
+/// 等价 createAction：固定 type，按 prepare 把参数组装成 PayloadAction
+pub struct ActionCreator<Args, P, M = ()> {
+    type_: &'static str,
+    prepare: Box<Prepare<Args, P, M>>,
+}
+
+impl<Args, P, M> ActionCreator<Args, P, M> {
+    pub fn call(&self, args: Args) -> PayloadAction<P, M> {
+        let Prepared {
+            payload,
+            meta,
+            error,
+        } = (self.prepare)(args);
+        PayloadAction {
+            type_: self.type_,
+            payload,
+            meta,
+            error,
+        }
+    }
+
+    pub fn type_(&self) -> &'static str {
+        self.type_
+    }
+
+    /// 等价 actionCreator.match：按 type 判断
+    pub fn matches(&self, action: &dyn Action) -> bool {
+        action.type_() == self.type_
+    }
+}
+
+/// 没有 prepare：参数本身就是 payload
+pub fn create_action<P: 'static>(type_: &'static str) -> ActionCreator<P, P> {
+    create_action_with_prepare(type_, Prepared::new)
+}
+
+/// 带 prepare：参数可以是任意形状（多个参数用元组），由 prepare 生成 payload / meta / error
+pub fn create_action_with_prepare<Args, P, M>(
+    type_: &'static str,
+    prepare: impl Fn(Args) -> Prepared<P, M> + 'static,
+) -> ActionCreator<Args, P, M> {
+    ActionCreator {
+        type_,
+        prepare: Box::new(prepare),
+    }
+}
+
+impl<P, M> From<PayloadAction<P, M>> for FsaAction<P, M> {
+    fn from(action: PayloadAction<P, M>) -> Self {
+        FsaAction {
+            type_: action.type_.into(),
+            payload: action.payload,
+            meta: action.meta,
+            error: action.error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_creator_uses_argument_as_payload() {
+        let add = create_action::<i32>("todos/add");
+        let action = add.call(3);
+        assert_eq!(add.type_(), "todos/add");
+        assert_eq!(action.type_(), "todos/add");
+        assert_eq!(action.payload, 3);
+        assert!(!action.is_error());
+        assert!(action.id().is_none());
+    }
+
+    #[test]
+    fn matches_compares_type() {
+        let add = create_action::<i32>("todos/add");
+        let remove = create_action::<i32>("todos/remove");
+        assert!(add.matches(&add.call(1)));
+        assert!(!add.matches(&remove.call(1)));
+    }
+
+    #[test]
+    fn prepare_sets_meta_and_error() {
+        let failed = create_action_with_prepare("todos/failed", |(text, code): (&str, u16)| {
+            Prepared::new(text.to_string()).with_meta(code).error()
+        });
+        let action = failed.call(("offline", 503));
+        assert_eq!(action.payload, "offline");
+        assert!(action.is_error());
+        let meta = action.meta().unwrap().downcast_ref::<u16>();
+        assert_eq!(meta, Some(&503));
+    }
+
+    #[test]
+    fn request_meta_gives_each_action_its_own_id() {
+        let fetch = create_action_with_prepare("todos/fetch", |page: u32| {
+            Prepared::new(page).with_meta(RequestMeta::new())
+        });
+        let first = fetch.call(1);
+        let second = fetch.call(1);
+        assert_eq!(first.id(), Some(first.meta.request_id.as_str()));
+        assert_ne!(first.id(), second.id());
+    }
+}
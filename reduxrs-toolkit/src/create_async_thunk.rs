@@ -0,0 +1,320 @@
+use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use reduxrs::core::optimistic::OptimisticPhase;
+use reduxrs::core::store::Store;
+use reduxrs::core::storet::Action;
+use reduxrs::effects::abort::{AbortController, AbortSignal, Aborted, abortable};
+
+use crate::nanoid::nanoid;
+
+/// 三个生命周期 action 共用的 meta（等价 RTK 的 meta.arg / meta.requestId）
+#[derive(Clone, Debug, PartialEq)]
+pub struct AsyncThunkMeta<Arg> {
+    pub arg: Arg,
+    pub request_id: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ThunkError<E> {
+    /// payload creator 返回了 Err
+    Rejected(E),
+    /// 通过 AbortSignal 取消
+    Aborted(Aborted),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ThunkStatus<T, E> {
+    Pending,
+    Fulfilled(T),
+    Rejected(ThunkError<E>),
+}
+
+/// `{prefix}/pending`、`{prefix}/fulfilled`、`{prefix}/rejected`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AsyncThunkAction<Arg, T, E> {
+    pub type_: String,
+    pub status: ThunkStatus<T, E>,
+    pub meta: AsyncThunkMeta<Arg>,
+}
+
+impl<Arg: 'static, T, E> Action for AsyncThunkAction<Arg, T, E> {
+    fn type_(&self) -> &str {
+        &self.type_
+    }
+
+    fn meta(&self) -> Option<&dyn Any> {
+        Some(&self.meta)
+    }
+
+    fn is_error(&self) -> bool {
+        matches!(self.status, ThunkStatus::Rejected(_))
+    }
+}
+
+impl<Arg, T, E> AsyncThunkAction<Arg, T, E> {
+    /// 配合 core::optimistic：pending 作为乐观 patch，fulfilled 提交，rejected 回滚
+    pub fn optimistic_phase(&self) -> OptimisticPhase {
+        let id = self.meta.request_id.clone();
+        match self.status {
+            ThunkStatus::Pending => OptimisticPhase::Begin(id),
+            ThunkStatus::Fulfilled(_) => OptimisticPhase::Commit(id),
+            ThunkStatus::Rejected(_) => OptimisticPhase::Revert(id),
+        }
+    }
+}
+
+/// dispatch 的最终结果：fulfilled 或 rejected action
+pub type ThunkResult<Arg, T, E> = AsyncThunkAction<Arg, T, E>;
+
+/// payload creator 能拿到的东西（等价 RTK 的 thunkAPI）
+pub struct AsyncThunkApi<S, A> {
+    pub store: Store<S, A>,
+    pub request_id: String,
+    pub signal: AbortSignal,
+}
+
+type PayloadFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>>>>;
+type PayloadCreator<S, A, Arg, T, E> = dyn Fn(Arg, AsyncThunkApi<S, A>) -> PayloadFuture<T, E>;
+
+/// 等价 createAsyncThunk
+pub struct AsyncThunk<S, A, Arg, T, E> {
+    type_prefix: &'static str,
+    payload_creator: Rc<PayloadCreator<S, A, Arg, T, E>>,
+}
+
+impl<S, A, Arg, T, E> Clone for AsyncThunk<S, A, Arg, T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            type_prefix: self.type_prefix,
+            payload_creator: self.payload_creator.clone(),
+        }
+    }
+}
+
+pub fn create_async_thunk<S, A, Arg, T, E, F, Fut>(
+    type_prefix: &'static str,
+    payload_creator: F,
+) -> AsyncThunk<S, A, Arg, T, E>
+where
+    F: Fn(Arg, AsyncThunkApi<S, A>) -> Fut + 'static,
+    Fut: Future<Output = Result<T, E>> + 'static,
+{
+    AsyncThunk {
+        type_prefix,
+        payload_creator: Rc::new(move |arg, api| Box::pin(payload_creator(arg, api))),
+    }
+}
+
+impl<S, A, Arg, T, E> AsyncThunk<S, A, Arg, T, E>
+where
+    S: Clone + 'static,
+    A: From<AsyncThunkAction<Arg, T, E>> + 'static,
+    Arg: Clone + 'static,
+    T: Clone + 'static,
+    E: Clone + 'static,
+{
+    pub fn pending_type(&self) -> String {
+        format!("{}/pending", self.type_prefix)
+    }
+
+    pub fn fulfilled_type(&self) -> String {
+        format!("{}/fulfilled", self.type_prefix)
+    }
+
+    pub fn rejected_type(&self) -> String {
+        format!("{}/rejected", self.type_prefix)
+    }
+
+    /// 生成交给 Store::dispatch_thunk 的 thunk：
+    /// 立即 dispatch pending，返回的 handle 被 await 时运行 payload creator，
+    /// 结束后 dispatch fulfilled / rejected
+    pub fn call(
+        &self,
+        arg: Arg,
+    ) -> impl FnOnce(&Store<S, A>) -> AsyncThunkHandle<Arg, T, E> + use<S, A, Arg, T, E> {
+        let thunk = self.clone();
+        move |store| thunk.start(store, arg)
+    }
+
+    fn start(&self, store: &Store<S, A>, arg: Arg) -> AsyncThunkHandle<Arg, T, E> {
+        let controller = AbortController::new();
+        let meta = AsyncThunkMeta {
+            arg: arg.clone(),
+            request_id: nanoid(),
+        };
+        store.dispatch(A::from(AsyncThunkAction {
+            type_: self.pending_type(),
+            status: ThunkStatus::Pending,
+            meta: meta.clone(),
+        }));
+
+        let payload = (self.payload_creator)(
+            arg,
+            AsyncThunkApi {
+                store: store.clone(),
+                request_id: meta.request_id.clone(),
+                signal: controller.signal(),
+            },
+        );
+        let signal = controller.signal();
+        let store = store.clone();
+        let (fulfilled_type, rejected_type) = (self.fulfilled_type(), self.rejected_type());
+        let request_id = meta.request_id.clone();
+        let future = async move {
+            let (type_, status) = match abortable(&signal, payload).await {
+                Ok(Ok(value)) => (fulfilled_type, ThunkStatus::Fulfilled(value)),
+                Ok(Err(error)) => (
+                    rejected_type,
+                    ThunkStatus::Rejected(ThunkError::Rejected(error)),
+                ),
+                Err(aborted) => (
+                    rejected_type,
+                    ThunkStatus::Rejected(ThunkError::Aborted(aborted)),
+                ),
+            };
+            let action = AsyncThunkAction {
+                type_,
+                status,
+                meta,
+            };
+            store.dispatch(A::from(action.clone()));
+            action
+        };
+
+        AsyncThunkHandle {
+            request_id,
+            controller,
+            future: Box::pin(future),
+        }
+    }
+}
+
+/// dispatch_thunk 的返回值：await 得到最终的 fulfilled / rejected action
+/// 需要被 await（或交给执行器）才会推进，和普通 future 一样
+pub struct AsyncThunkHandle<Arg, T, E> {
+    request_id: String,
+    controller: AbortController,
+    future: Pin<Box<dyn Future<Output = ThunkResult<Arg, T, E>>>>,
+}
+
+impl<Arg, T, E> AsyncThunkHandle<Arg, T, E> {
+    pub fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    /// 取消：payload creator 的 future 被 drop，dispatch rejected（ThunkError::Aborted）
+    pub fn abort(&self, reason: impl Into<String>) {
+        self.controller.abort(reason);
+    }
+
+    /// 等价 RTK 的 .unwrap()：fulfilled 得到 payload，否则得到错误
+    pub async fn unwrap_payload(self) -> Result<T, ThunkError<E>> {
+        match self.await.status {
+            ThunkStatus::Fulfilled(value) => Ok(value),
+            ThunkStatus::Rejected(error) => Err(error),
+            ThunkStatus::Pending => unreachable!("an async thunk never resolves to pending"),
+        }
+    }
+}
+
+impl<Arg, T, E> Future for AsyncThunkHandle<Arg, T, E> {
+    type Output = ThunkResult<Arg, T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().future.as_mut().poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::pending;
+    use std::pin::pin;
+    use std::task::Waker;
+
+    use super::*;
+
+    type Lifecycle = AsyncThunkAction<i32, i32, String>;
+
+    // 记录收到的 action type
+    fn store() -> Store<Vec<String>, Lifecycle> {
+        Store::new(
+            |types: &Vec<String>, action: &Lifecycle| {
+                let mut next = types.clone();
+                next.push(action.type_.clone());
+                next
+            },
+            Vec::new(),
+        )
+    }
+
+    fn poll_once<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+        future.poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    fn halve() -> AsyncThunk<Vec<String>, Lifecycle, i32, i32, String> {
+        create_async_thunk("math/halve", |n: i32, _| async move {
+            if n % 2 == 0 {
+                Ok(n / 2)
+            } else {
+                Err(format!("{n} is odd"))
+            }
+        })
+    }
+
+    #[test]
+    fn handle_resolves_to_fulfilled_payload() {
+        let store = store();
+        let handle = store.dispatch_thunk(halve().call(8));
+        assert_eq!(store.get_state(), ["math/halve/pending"]);
+        let request_id = handle.request_id().to_string();
+
+        let Poll::Ready(action) = poll_once(pin!(handle)) else {
+            panic!("payload creator is ready immediately");
+        };
+        assert_eq!(action.status, ThunkStatus::Fulfilled(4));
+        assert_eq!(action.meta.request_id, request_id);
+        assert_eq!(
+            store.get_state(),
+            ["math/halve/pending", "math/halve/fulfilled"]
+        );
+    }
+
+    #[test]
+    fn unwrap_payload_surfaces_rejection() {
+        let store = store();
+        let unwrapped = store.dispatch_thunk(halve().call(3)).unwrap_payload();
+        assert_eq!(
+            poll_once(pin!(unwrapped)),
+            Poll::Ready(Err(ThunkError::Rejected("3 is odd".to_string())))
+        );
+        assert_eq!(
+            store.get_state(),
+            ["math/halve/pending", "math/halve/rejected"]
+        );
+    }
+
+    #[test]
+    fn abort_rejects_pending_thunk() {
+        let store = store();
+        let forever = create_async_thunk("never", |_: i32, _| pending::<Result<i32, String>>());
+        let mut handle = pin!(store.dispatch_thunk(forever.call(1)));
+        assert!(poll_once(handle.as_mut()).is_pending());
+
+        handle.abort("stop");
+        let Poll::Ready(action) = poll_once(handle) else {
+            panic!("aborted thunk should resolve");
+        };
+        assert_eq!(
+            action.status,
+            ThunkStatus::Rejected(ThunkError::Aborted(Aborted {
+                reason: "stop".to_string()
+            }))
+        );
+        assert!(action.is_error());
+        assert_eq!(store.get_state(), ["never/pending", "never/rejected"]);
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use reduxrs::core::store_api::StoreApi;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+type Construct<A> = dyn Fn(Value) -> Result<A, DynamicActionError>;
+
+/// 把 `{ "type": "todos/add", "payload": {...} }` 形状的 JSON 转成具体的 A：
+/// 给脚本 / 插件 / CLI / 网络消息等动态输入用，结果照常 dispatch
+pub struct DynamicActionRegistry<A> {
+    constructors: HashMap<String, Box<Construct<A>>>,
+}
+
+/// DynamicActionRegistry 解析失败的原因
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DynamicActionError {
+    /// 不是合法 JSON，或者不是带字符串 type 的对象
+    Malformed(String),
+    /// type 没有注册
+    UnknownType(String),
+    /// payload 反序列化失败；path 同 HydrateError::path
+    Payload {
+        type_: String,
+        path: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for DynamicActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynamicActionError::Malformed(message) => write!(f, "malformed action: {message}"),
+            DynamicActionError::UnknownType(type_) => write!(f, "unknown action type: {type_}"),
+            DynamicActionError::Payload {
+                type_,
+                path,
+                message,
+            } => write!(f, "invalid payload for {type_} at {path}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for DynamicActionError {}
+
+impl<A: 'static> Default for DynamicActionRegistry<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: 'static> DynamicActionRegistry<A> {
+    pub fn new() -> Self {
+        Self {
+            constructors: HashMap::new(),
+        }
+    }
+
+    /// payload 反序列化成 P 之后交给 create；没有 payload 字段时按 null 处理（P = () 即可）
+    /// 同一个 type 重复注册时后者覆盖前者
+    pub fn register<P: DeserializeOwned>(
+        mut self,
+        type_: impl Into<String>,
+        create: impl Fn(P) -> A + 'static,
+    ) -> Self {
+        let type_ = type_.into();
+        let name = type_.clone();
+        self.constructors.insert(
+            type_,
+            Box::new(move |payload| {
+                serde_path_to_error::deserialize(payload)
+                    .map(&create)
+                    .map_err(|error| DynamicActionError::Payload {
+                        type_: name.clone(),
+                        path: error.path().to_string(),
+                        message: error.inner().to_string(),
+                    })
+            }),
+        );
+        self
+    }
+
+    pub fn is_registered(&self, type_: &str) -> bool {
+        self.constructors.contains_key(type_)
+    }
+
+    pub fn types(&self) -> impl Iterator<Item = &str> {
+        self.constructors.keys().map(String::as_str)
+    }
+
+    pub fn parse(&self, json: &str) -> Result<A, DynamicActionError> {
+        let value = serde_json::from_str(json)
+            .map_err(|error| DynamicActionError::Malformed(error.to_string()))?;
+        self.from_value(value)
+    }
+
+    pub fn from_value(&self, value: Value) -> Result<A, DynamicActionError> {
+        let Value::Object(mut object) = value else {
+            return Err(DynamicActionError::Malformed(
+                "expected an object".to_string(),
+            ));
+        };
+        let Some(Value::String(type_)) = object.remove("type") else {
+            return Err(DynamicActionError::Malformed(
+                "missing string field `type`".to_string(),
+            ));
+        };
+        let construct = self
+            .constructors
+            .get(&type_)
+            .ok_or(DynamicActionError::UnknownType(type_))?;
+        construct(object.remove("payload").unwrap_or(Value::Null))
+    }
+
+    /// 解析成功才 dispatch；失败时 store 不受影响
+    pub fn dispatch<S>(
+        &self,
+        store: &dyn StoreApi<S, A>,
+        json: &str,
+    ) -> Result<(), DynamicActionError> {
+        store.dispatch(self.parse(json)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use reduxrs::core::store::Store;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, Deserialize)]
+    struct Todo {
+        text: String,
+        tags: Vec<String>,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum TodoAction {
+        Add(Todo),
+        Clear,
+    }
+
+    fn registry() -> DynamicActionRegistry<TodoAction> {
+        DynamicActionRegistry::new()
+            .register("todos/add", TodoAction::Add)
+            .register("todos/clear", |()| TodoAction::Clear)
+    }
+
+    fn malformed(json: &str) -> bool {
+        matches!(
+            registry().parse(json),
+            Err(DynamicActionError::Malformed(_))
+        )
+    }
+
+    #[test]
+    fn parses_registered_types() {
+        let action = registry()
+            .parse(r#"{ "type": "todos/add", "payload": { "text": "a", "tags": ["x"] } }"#)
+            .unwrap();
+        let todo = Todo {
+            text: "a".to_string(),
+            tags: vec!["x".to_string()],
+        };
+        assert_eq!(action, TodoAction::Add(todo));
+        // 没有 payload 按 null 处理
+        assert_eq!(
+            registry().parse(r#"{ "type": "todos/clear" }"#),
+            Ok(TodoAction::Clear)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(malformed("{"));
+        assert!(malformed("[1, 2]"));
+        assert!(malformed(r#"{ "payload": null }"#));
+        assert!(malformed(r#"{ "type": 1 }"#));
+    }
+
+    #[test]
+    fn reports_unknown_type_and_payload_path() {
+        assert_eq!(
+            registry().parse(r#"{ "type": "todos/remove" }"#),
+            Err(DynamicActionError::UnknownType("todos/remove".to_string()))
+        );
+        let Err(DynamicActionError::Payload { type_, path, .. }) = registry()
+            .parse(r#"{ "type": "todos/add", "payload": { "text": "a", "tags": ["x", 2] } }"#)
+        else {
+            panic!("expected a payload error");
+        };
+        assert_eq!(type_, "todos/add");
+        assert_eq!(path, "tags[1]");
+        // 缺少 payload 时 null 不是 Todo
+        assert!(matches!(
+            registry().parse(r#"{ "type": "todos/add" }"#),
+            Err(DynamicActionError::Payload { .. })
+        ));
+    }
+
+    #[test]
+    fn dispatch_leaves_store_untouched_on_error() {
+        let store = Store::new(
+            |count: &i32, action: &TodoAction| match action {
+                TodoAction::Add(_) => count + 1,
+                TodoAction::Clear => 0,
+            },
+            0,
+        );
+        let seen = Rc::new(Cell::new(0));
+        let notified = seen.clone();
+        let _sub = store.subscribe(move |_: &i32, _: &TodoAction| notified.set(notified.get() + 1));
+        let registry = registry();
+        let add = r#"{ "type": "todos/add", "payload": { "text": "a", "tags": [] } }"#;
+        assert!(registry.dispatch(&store, add).is_ok());
+        assert!(
+            registry
+                .dispatch(&store, r#"{ "type": "todos/add" }"#)
+                .is_err()
+        );
+        assert!(registry.dispatch(&store, "not json").is_err());
+        assert_eq!(store.get_state(), 1);
+        assert_eq!(seen.get(), 1);
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::configure_store::ConfigureStore;
+
+/// 反序列化失败的位置和原因
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HydrateError {
+    /// 出错字段的路径，例如 `todos.items[2].done`；根节点为 `.`
+    pub path: String,
+    /// serde 的错误信息（包含期望的类型；从字符串解析时还有行列号）
+    pub message: String,
+}
+
+impl fmt::Display for HydrateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for HydrateError {}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for HydrateError {
+    fn from(error: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        Self {
+            path: error.path().to_string(),
+            message: error.inner().to_string(),
+        }
+    }
+}
+
+impl<S: DeserializeOwned + 'static, A: 'static> ConfigureStore<S, A> {
+    /// 从 JSON 加载 preloaded state，任何字段出错都整体失败
+    /// 只支持 JSON：TOML 等其他格式请先在外部转换成 JSON（本 crate 不依赖 toml）
+    pub fn preloaded_json(self, json: &str) -> Result<Self, HydrateError> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let state = serde_path_to_error::deserialize(&mut deserializer)?;
+        Ok(self.preloaded_state(state))
+    }
+}
+
+impl<S: Serialize + DeserializeOwned + 'static, A: 'static> ConfigureStore<S, A> {
+    /// 按顶层 slice 逐个加载：解析失败的 slice 保留 reducer 默认值，错误一并返回
+    /// JSON 本身不合法、或 state 不是对象时整体失败
+    pub fn preloaded_json_with_fallback(
+        self,
+        json: &str,
+    ) -> Result<(Self, Vec<HydrateError>), HydrateError> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let loaded: Value = serde_path_to_error::deserialize(&mut deserializer)?;
+        let defaults = serde_json::to_value(&self.initial_state).map_err(|e| HydrateError {
+            path: ".".to_string(),
+            message: e.to_string(),
+        })?;
+        let (Value::Object(loaded), Value::Object(mut merged)) = (loaded, defaults) else {
+            return self.preloaded_json(json).map(|store| (store, Vec::new()));
+        };
+
+        let mut errors = Vec::new();
+        for (key, value) in loaded {
+            let mut candidate = merged.clone();
+            candidate.insert(key, value);
+            match serde_path_to_error::deserialize::<_, S>(Value::Object(candidate.clone())) {
+                Ok(_) => merged = candidate,
+                Err(error) => errors.push(error.into()),
+            }
+        }
+        let state = serde_path_to_error::deserialize(Value::Object(merged))?;
+        Ok((self.preloaded_state(state), errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::configure_store::configure_store;
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Todos {
+        items: Vec<String>,
+        done: bool,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct AppState {
+        todos: Todos,
+        count: i32,
+    }
+
+    fn defaults() -> AppState {
+        AppState {
+            todos: Todos::default(),
+            count: 7,
+        }
+    }
+
+    fn builder() -> ConfigureStore<AppState, ()> {
+        configure_store(|state: &AppState, _: &()| state.clone(), defaults())
+    }
+
+    #[test]
+    fn strict_hydration_loads_whole_state() {
+        let store = builder()
+            .preloaded_json(r#"{"todos":{"items":["a"],"done":true},"count":1}"#)
+            .unwrap()
+            .build();
+        assert_eq!(
+            store.get_state(),
+            AppState {
+                todos: Todos {
+                    items: vec!["a".into()],
+                    done: true,
+                },
+                count: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn strict_hydration_reports_path_and_expected_type() {
+        let Err(error) =
+            builder().preloaded_json(r#"{"todos":{"items":["a", 3],"done":true},"count":1}"#)
+        else {
+            panic!("hydration should fail");
+        };
+        assert_eq!(error.path, "todos.items[1]");
+        assert!(error.message.contains("expected a string"), "{error}");
+    }
+
+    #[test]
+    fn fallback_keeps_default_for_broken_slice() {
+        let (configured, errors) = builder()
+            .preloaded_json_with_fallback(r#"{"todos":{"items":"oops","done":true},"count":3}"#)
+            .unwrap();
+        assert_eq!(
+            configured.build().get_state(),
+            AppState {
+                todos: Todos::default(),
+                count: 3,
+            }
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "todos.items");
+    }
+
+    #[test]
+    fn fallback_fills_missing_slices_and_reports_each_failure() {
+        let (configured, errors) = builder()
+            .preloaded_json_with_fallback(r#"{"count":"three"}"#)
+            .unwrap();
+        assert_eq!(configured.build().get_state(), defaults());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "count");
+
+        let (configured, errors) = builder()
+            .preloaded_json_with_fallback(r#"{"todos":{"done":1},"count":null}"#)
+            .unwrap();
+        assert_eq!(configured.build().get_state(), defaults());
+        let paths: Vec<_> = errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["count", "todos.done"]);
+    }
+
+    #[test]
+    fn fallback_rejects_malformed_json() {
+        assert!(
+            builder()
+                .preloaded_json_with_fallback("{\"count\":")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn fallback_on_non_object_state_is_strict() {
+        let (configured, errors) = configure_store(|state: &i32, _: &()| *state, 0)
+            .preloaded_json_with_fallback("5")
+            .unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(configured.build().get_state(), 5);
+        assert!(
+            configure_store(|state: &i32, _: &()| *state, 0)
+                .preloaded_json_with_fallback("\"5\"")
+                .is_err()
+        );
+    }
+}
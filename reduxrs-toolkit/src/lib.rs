@@ -1,3 +1,12 @@
+pub mod configure_store;
+pub mod create_action;
+pub mod create_async_thunk;
+#[cfg(feature = "json")]
+pub mod dynamic_action;
+#[cfg(feature = "json")]
+pub mod hydrate;
+pub mod nanoid;
+
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
@@ -0,0 +1,61 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// 与 RTK 的 nanoid 相同的 URL 安全字母表（64 个字符，每个字符 6 bit）
+const URL_ALPHABET: &[u8; 64] = b"ModuleSymbhasOwnPr-0123456789ABCDEFGHNRVfgctiUvz_KqYTJkLxpZXIjQW";
+
+const DEFAULT_SIZE: usize = 21;
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// 非加密安全的短 id（等价 RTK 的 nanoid），用于 request id 等
+pub fn nanoid() -> String {
+    nanoid_with_size(DEFAULT_SIZE)
+}
+
+pub fn nanoid_with_size(size: usize) -> String {
+    let mut id = String::with_capacity(size);
+    let mut bits = 0u64;
+    let mut available = 0;
+    while id.len() < size {
+        if available < 6 {
+            bits = random_u64();
+            available = 64;
+        }
+        id.push(URL_ALPHABET[(bits & 63) as usize] as char);
+        bits >>= 6;
+        available -= 6;
+    }
+    id
+}
+
+// RandomState 每个实例带随机 key；再混入计数器，保证同一线程内连续调用也不同
+fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn uses_url_alphabet_and_requested_size() {
+        assert_eq!(nanoid().len(), DEFAULT_SIZE);
+        assert_eq!(nanoid_with_size(0), "");
+        // 超过一个 u64 能提供的字符数
+        let id = nanoid_with_size(100);
+        assert_eq!(id.len(), 100);
+        assert!(id.bytes().all(|b| URL_ALPHABET.contains(&b)));
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        let ids: HashSet<String> = (0..1000).map(|_| nanoid()).collect();
+        assert_eq!(ids.len(), 1000);
+    }
+}
@@ -1,2 +1,28 @@
+#[cfg(feature = "std")]
+pub mod actor;
+pub mod autobatch;
+pub mod bind_action_creators;
+#[cfg(feature = "std")]
+pub mod crash_reporter;
+pub mod dedup;
+pub mod derived;
+pub mod diagnostics;
+pub mod enhancer;
+pub mod fsa;
+pub mod invariant;
+pub mod journal;
+pub mod matcher;
+pub mod metrics;
+pub mod middleware;
+pub mod optimistic;
+#[cfg(feature = "patch")]
+pub mod patch;
+pub mod rate_limit;
+pub mod schedule;
+pub mod scope;
+pub mod snapshot;
 pub mod store;
-pub mod storet;
\ No newline at end of file
+pub mod store_api;
+pub mod storet;
+pub mod timer;
+pub mod watch;
\ No newline at end of file
@@ -0,0 +1,289 @@
+use std::fmt;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use super::store::{Store, Version};
+
+/// actor 线程已经退出（shutdown 或 store panic），action 没有送达
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActorStopped;
+
+impl fmt::Display for ActorStopped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The store actor has stopped.")
+    }
+}
+
+impl std::error::Error for ActorStopped {}
+
+enum Message<A> {
+    Dispatch(A),
+    Shutdown,
+}
+
+/// 向 actor 发送 action：可以跨线程克隆，按发送顺序依次 dispatch
+pub struct DispatchHandle<A> {
+    sender: Sender<Message<A>>,
+}
+
+impl<A> Clone for DispatchHandle<A> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<A> DispatchHandle<A> {
+    pub fn dispatch(&self, action: A) -> Result<(), ActorStopped> {
+        self.sender
+            .send(Message::Dispatch(action))
+            .map_err(|_| ActorStopped)
+    }
+}
+
+struct Latest<S> {
+    state: S,
+    version: Version,
+    stopped: bool,
+}
+
+struct Shared<S> {
+    latest: Mutex<Latest<S>>,
+    changed: Condvar,
+}
+
+impl<S> Shared<S> {
+    // 持锁期间只有赋值 / clone；即使 poison 也继续读取最近的 state
+    fn lock(&self) -> MutexGuard<'_, Latest<S>> {
+        self.latest
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 读取 actor 最近一次通知的 state（类似 watch channel）：可以跨线程克隆
+pub struct StateReader<S> {
+    shared: Arc<Shared<S>>,
+}
+
+impl<S> Clone for StateReader<S> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<S: Clone> StateReader<S> {
+    pub fn get(&self) -> S {
+        self.shared.lock().state.clone()
+    }
+
+    /// 不克隆，直接在锁内读取（f 里不要阻塞）
+    pub fn with<R>(&self, f: impl FnOnce(&S) -> R) -> R {
+        f(&self.shared.lock().state)
+    }
+
+    pub fn version(&self) -> Version {
+        self.shared.lock().version
+    }
+
+    /// 阻塞直到 version 大于 since，返回新的 (state, version)；actor 已退出且没有更新时返回 None
+    pub fn wait_for_change(&self, since: Version) -> Option<(S, Version)> {
+        let guard = self
+            .shared
+            .changed
+            .wait_while(self.shared.lock(), |latest| {
+                latest.version <= since && !latest.stopped
+            })
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        (guard.version > since).then(|| (guard.state.clone(), guard.version))
+    }
+}
+
+/// 运行在独立线程上的 store：调用方只持有 DispatchHandle 和 StateReader，
+/// 不需要共享 Rc / RefCell；所有 action 在 actor 线程上按到达顺序处理
+pub struct StoreActor<S, A> {
+    handle: DispatchHandle<A>,
+    reader: StateReader<S>,
+    thread: JoinHandle<()>,
+}
+
+impl<S, A> StoreActor<S, A>
+where
+    S: Clone + Send + 'static,
+    A: Send + 'static,
+{
+    /// create 在 actor 线程上构造 store（Store 不是 Send）：middleware、enhancer、effects 都在里面装好
+    pub fn spawn(create: impl FnOnce() -> Store<S, A> + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel::<Message<A>>();
+        let (ready_sender, ready_receiver) = mpsc::channel();
+
+        let thread = thread::spawn(move || {
+            let store = create();
+            let shared = Arc::new(Shared {
+                latest: Mutex::new(Latest {
+                    state: store.get_state(),
+                    version: store.version(),
+                    stopped: false,
+                }),
+                changed: Condvar::new(),
+            });
+            let _ = ready_sender.send(shared.clone());
+            let _stopped = StoppedGuard(shared.clone());
+
+            let publish = shared.clone();
+            let _subscription = store.subscribe_versioned(move |state, _, version| {
+                let mut latest = publish.lock();
+                latest.state = state.clone();
+                latest.version = version;
+                drop(latest);
+                publish.changed.notify_all();
+            });
+
+            for message in receiver {
+                match message {
+                    Message::Dispatch(action) => store.dispatch(action),
+                    Message::Shutdown => break,
+                }
+            }
+        });
+
+        let shared = ready_receiver
+            .recv()
+            .expect("The store actor panicked while creating the store.");
+        Self {
+            handle: DispatchHandle { sender },
+            reader: StateReader { shared },
+            thread,
+        }
+    }
+
+    pub fn handle(&self) -> DispatchHandle<A> {
+        self.handle.clone()
+    }
+
+    pub fn reader(&self) -> StateReader<S> {
+        self.reader.clone()
+    }
+
+    /// 处理完已经发送的 action 后退出，等待线程结束；store panic 时返回 Err
+    pub fn shutdown(self) -> thread::Result<()> {
+        let _ = self.handle.sender.send(Message::Shutdown);
+        self.thread.join()
+    }
+}
+
+// actor 退出（包括 panic）时唤醒所有 wait_for_change
+struct StoppedGuard<S>(Arc<Shared<S>>);
+
+impl<S> Drop for StoppedGuard<S> {
+    fn drop(&mut self) {
+        self.0.lock().stopped = true;
+        self.0.changed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_store() -> Store<Vec<(u8, u32)>, (u8, u32)> {
+        Store::new(
+            |state: &Vec<(u8, u32)>, action: &(u8, u32)| {
+                assert!(action.0 != u8::MAX, "poisoned action");
+                let mut next = state.clone();
+                next.push(*action);
+                next
+            },
+            Vec::new(),
+        )
+    }
+
+    // 等到 version 至少为 target；actor 退出时返回最后看到的 state
+    fn wait_until(reader: &StateReader<Vec<(u8, u32)>>, target: Version) -> Vec<(u8, u32)> {
+        let mut seen = reader.version();
+        while seen < target {
+            match reader.wait_for_change(seen) {
+                Some((_, version)) => seen = version,
+                None => break,
+            }
+        }
+        reader.get()
+    }
+
+    #[test]
+    fn dispatches_from_many_threads_keep_per_sender_order() {
+        let actor = StoreActor::spawn(log_store);
+        let senders: Vec<_> = (0..4u8)
+            .map(|sender| {
+                let handle = actor.handle();
+                thread::spawn(move || {
+                    for n in 0..50 {
+                        handle.dispatch((sender, n)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for sender in senders {
+            sender.join().unwrap();
+        }
+        let log = wait_until(&actor.reader(), 200);
+        assert_eq!(log.len(), 200);
+        for sender in 0..4u8 {
+            let order: Vec<u32> = log
+                .iter()
+                .filter(|(from, _)| *from == sender)
+                .map(|(_, n)| *n)
+                .collect();
+            assert_eq!(order, (0..50).collect::<Vec<_>>());
+        }
+        assert_eq!(actor.reader().version(), 200);
+        actor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn wait_for_change_wakes_on_dispatch_and_on_shutdown() {
+        let actor = StoreActor::spawn(log_store);
+        let reader = actor.reader();
+        let waiter = thread::spawn(move || reader.wait_for_change(0));
+        actor.handle().dispatch((0, 1)).unwrap();
+        assert_eq!(waiter.join().unwrap(), Some((vec![(0, 1)], 1)));
+
+        let reader = actor.reader();
+        let waiter = thread::spawn(move || reader.wait_for_change(1));
+        actor.shutdown().unwrap();
+        assert_eq!(waiter.join().unwrap(), None);
+    }
+
+    #[test]
+    fn shutdown_processes_queued_actions_and_joins() {
+        let actor = StoreActor::spawn(log_store);
+        let (handle, reader) = (actor.handle(), actor.reader());
+        for n in 0..10 {
+            handle.dispatch((0, n)).unwrap();
+        }
+        actor.shutdown().unwrap();
+        // 线程已经结束：state 已经包含全部 action，之后的 dispatch 失败
+        assert_eq!(reader.get().len(), 10);
+        assert_eq!(reader.wait_for_change(10), None);
+        assert_eq!(handle.dispatch((0, 10)), Err(ActorStopped));
+    }
+
+    #[test]
+    fn reducer_panic_stops_the_actor_instead_of_hanging() {
+        let actor = StoreActor::spawn(log_store);
+        let (handle, reader) = (actor.handle(), actor.reader());
+        handle.dispatch((0, 1)).unwrap();
+        handle.dispatch((u8::MAX, 0)).unwrap();
+        // 等待者被唤醒而不是一直阻塞
+        let last = wait_until(&reader, 2);
+        let result = actor.shutdown();
+        assert_eq!(last, vec![(0, 1)]);
+        assert_eq!(reader.wait_for_change(1), None);
+        assert_eq!(handle.dispatch((0, 2)), Err(ActorStopped));
+        assert!(result.is_err());
+    }
+}
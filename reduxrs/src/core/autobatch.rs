@@ -0,0 +1,65 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::time::Duration;
+
+use super::timer::{Timer, TimerId};
+
+/// 把一次 flush 安排到“稍后”（微任务 / 帧末 / 定时器……），由调用方的运行时决定
+pub type ScheduleFn = dyn Fn(Box<dyn FnOnce()>) + 'static;
+
+/// 推迟的通知什么时候发出
+#[derive(Clone)]
+pub enum BatchSchedule {
+    /// 只在调用 Store::flush_batched 时
+    Manual,
+    /// 第一次推迟时调用回调安排一次 flush
+    Callback(Rc<ScheduleFn>),
+    /// 第一次推迟后经过这么久 flush，使用 store 的定时器（见 Store::set_timer）
+    Timeout(Duration),
+}
+
+/// 等价 RTK 的 autoBatchEnhancer：低优先级 action 立即更新 state，
+/// 但 listener 通知合并到下一次 flush，只收到最后一个 action
+pub(crate) struct AutoBatch<A> {
+    is_low_priority: Box<dyn Fn(&A) -> bool + 'static>,
+    schedule: BatchSchedule,
+    pending: Option<A>,
+    // Timeout 安排的 flush：本批被提前取走时清掉，否则它会提前 flush 下一批
+    armed: Option<(Rc<dyn Timer>, TimerId)>,
+}
+
+impl<A> AutoBatch<A> {
+    pub(crate) fn new(
+        is_low_priority: Box<dyn Fn(&A) -> bool + 'static>,
+        schedule: BatchSchedule,
+    ) -> Self {
+        Self {
+            is_low_priority,
+            schedule,
+            pending: None,
+            armed: None,
+        }
+    }
+
+    pub(crate) fn is_low_priority(&self, action: &A) -> bool {
+        (self.is_low_priority)(action)
+    }
+
+    /// 攒下这个 action；本批第一次推迟时返回需要执行的调度方式
+    pub(crate) fn defer(&mut self, action: A) -> Option<BatchSchedule> {
+        let first = self.pending.replace(action).is_none();
+        first.then(|| self.schedule.clone())
+    }
+
+    /// 记下本批的 flush 定时器
+    pub(crate) fn arm(&mut self, timer: Rc<dyn Timer>, id: TimerId) {
+        self.armed = Some((timer, id));
+    }
+
+    pub(crate) fn take(&mut self) -> Option<A> {
+        if let Some((timer, id)) = self.armed.take() {
+            timer.clear_timeout(id);
+        }
+        self.pending.take()
+    }
+}
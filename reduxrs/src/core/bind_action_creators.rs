@@ -0,0 +1,121 @@
+use super::store::Store;
+
+/// 把一个 action creator 绑定到 store：调用返回的闭包 == store.dispatch(creator(payload))
+/// 多参数的 creator 用元组作为 payload
+pub fn bind_action_creator<S, A, P, F>(
+    store: &Store<S, A>,
+    creator: F,
+) -> impl Fn(P) + use<S, A, P, F>
+where
+    S: Clone + 'static,
+    A: 'static,
+    F: Fn(P) -> A + 'static,
+{
+    let store = store.clone();
+    move |payload| store.dispatch(creator(payload))
+}
+
+/// 等价 bindActionCreators：生成一个持有 store 的结构体，每个方法直接 dispatch
+///
+/// ```
+/// use reduxrs::bind_action_creators;
+/// use reduxrs::core::store::Store;
+///
+/// #[derive(Clone, Debug)]
+/// enum TodoAction {
+///     Add(String),
+///     Toggle(usize),
+/// }
+///
+/// bind_action_creators! {
+///     pub struct TodoActions<Vec<String>, TodoAction> {
+///         add(text: String) => TodoAction::Add(text),
+///         toggle(index: usize) => TodoAction::Toggle(index),
+///     }
+/// }
+///
+/// let store = Store::new(
+///     |s: &Vec<String>, a: &TodoAction| match a {
+///         TodoAction::Add(text) => [s.clone(), vec![text.clone()]].concat(),
+///         TodoAction::Toggle(_) => s.clone(),
+///     },
+///     Vec::new(),
+/// );
+/// let actions = TodoActions::new(&store);
+/// actions.add("write docs".to_string());
+/// assert_eq!(store.get_state(), vec!["write docs".to_string()]);
+/// ```
+#[macro_export]
+macro_rules! bind_action_creators {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident<$state:ty, $action:ty> {
+            $( $method:ident ( $( $arg:ident : $arg_ty:ty ),* $(,)? ) => $creator:expr ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        $vis struct $name {
+            store: $crate::core::store::Store<$state, $action>,
+        }
+
+        impl $name {
+            $vis fn new(store: &$crate::core::store::Store<$state, $action>) -> Self {
+                Self {
+                    store: store.clone(),
+                }
+            }
+
+            $(
+                $vis fn $method(&self, $( $arg: $arg_ty ),*) {
+                    self.store.dispatch($creator);
+                }
+            )*
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    enum Action {
+        Add(i32),
+        Move(usize, i32),
+    }
+
+    fn store(initial: Vec<i32>) -> Store<Vec<i32>, Action> {
+        Store::new(
+            |state: &Vec<i32>, action: &Action| {
+                let mut next = state.clone();
+                match *action {
+                    Action::Add(n) => next.push(n),
+                    Action::Move(index, n) => next[index] = n,
+                }
+                next
+            },
+            initial,
+        )
+    }
+
+    #[test]
+    fn bound_creator_dispatches() {
+        let store = store(Vec::new());
+        let add = bind_action_creator(&store, Action::Add);
+        add(1);
+        add(2);
+        assert_eq!(store.get_state(), vec![1, 2]);
+    }
+
+    #[test]
+    fn tuple_payload_spreads_into_creator() {
+        let store = store(vec![0, 0]);
+        let set = bind_action_creator(&store, |(index, n): (usize, i32)| Action::Move(index, n));
+        set((1, 5));
+        assert_eq!(store.get_state(), vec![0, 5]);
+    }
+}
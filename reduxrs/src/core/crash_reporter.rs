@@ -0,0 +1,162 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::Sender;
+
+use super::middleware::{Middleware, MiddlewareApi, Next};
+
+/// 一次崩溃的现场：触发的 action + 崩溃后的 state 快照 + panic 信息
+#[derive(Clone, Debug)]
+pub struct CrashReport<S, A> {
+    pub action: A,
+    pub state: S,
+    pub message: String,
+}
+
+pub type CrashSink<S, A> = dyn Fn(CrashReport<S, A>) + 'static;
+
+/// 捕获下游 middleware / reducer / listeners 的 panic，打包后交给 sink，
+/// 不再让一次 panic 打断调用方的 dispatch
+/// 要覆盖所有下游，需要第一个 apply
+pub struct CrashReporter<S, A> {
+    sink: Box<CrashSink<S, A>>,
+}
+
+impl<S, A> CrashReporter<S, A> {
+    /// sink 是普通回调
+    pub fn new(sink: impl Fn(CrashReport<S, A>) + 'static) -> Self {
+        Self {
+            sink: Box::new(sink),
+        }
+    }
+
+    /// sink 是 channel：接收端断开时直接丢弃报告
+    pub fn to_channel(sender: Sender<CrashReport<S, A>>) -> Self
+    where
+        S: 'static,
+        A: 'static,
+    {
+        Self::new(move |report| {
+            let _ = sender.send(report);
+        })
+    }
+}
+
+impl<S: Clone + 'static, A: Clone + 'static> Middleware<S, A> for CrashReporter<S, A> {
+    fn handle(&self, api: &dyn MiddlewareApi<S, A>, action: A, next: Next<A>) {
+        let forwarded = action.clone();
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| next(forwarded))) {
+            (self.sink)(CrashReport {
+                action,
+                state: api.get_state(),
+                message: panic_message(payload.as_ref()),
+            });
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::core::middleware::split_action;
+    use crate::core::store::Store;
+
+    type Reports = Rc<RefCell<Vec<CrashReport<i32, i32>>>>;
+
+    fn guarded_store() -> Store<i32, i32> {
+        Store::new(
+            |state: &i32, action: &i32| {
+                assert!(*action >= 0, "negative action {action}");
+                state + action
+            },
+            0,
+        )
+    }
+
+    fn reporter() -> (CrashReporter<i32, i32>, Reports) {
+        let reports: Reports = Rc::new(RefCell::new(Vec::new()));
+        let sink = reports.clone();
+        let reporter = CrashReporter::new(move |report| sink.borrow_mut().push(report));
+        (reporter, reports)
+    }
+
+    #[test]
+    fn reducer_panic_is_reported_and_state_kept() {
+        let store = guarded_store();
+        let (reporter, reports) = reporter();
+        store.apply_middleware(reporter);
+        store.dispatch(1);
+        store.dispatch(-5);
+        {
+            let reports = reports.borrow();
+            assert_eq!(reports.len(), 1);
+            assert_eq!(reports[0].action, -5);
+            assert_eq!(reports[0].state, 1);
+            assert_eq!(reports[0].message, "negative action -5");
+        }
+        assert_eq!(store.get_state(), 1);
+        store.dispatch(2);
+        assert_eq!(store.get_state(), 3);
+        assert_eq!(reports.borrow().len(), 1);
+    }
+
+    #[test]
+    fn listener_panic_is_reported_with_committed_state() {
+        let store = guarded_store();
+        let (sender, receiver) = mpsc::channel();
+        store.apply_middleware(CrashReporter::to_channel(sender));
+        let _sub = store.subscribe(|state: &i32, _: &i32| {
+            if *state == 3 {
+                panic!("listener failed at {state}");
+            }
+        });
+        store.dispatch(3);
+        let report = receiver.try_recv().unwrap();
+        assert_eq!((report.action, report.state), (3, 3));
+        assert_eq!(report.message, "listener failed at 3");
+        store.dispatch(1);
+        assert_eq!(store.get_state(), 4);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn later_next_in_same_pass_stays_inside_reporter() {
+        let store = guarded_store();
+        let (reporter, reports) = reporter();
+        // split 在外层：每个拆出来的 action 各自经过 reporter
+        store.apply_middleware(split_action(
+            |_: &dyn MiddlewareApi<i32, i32>, action: i32| {
+                if action == 100 {
+                    vec![-1, -2, 3]
+                } else {
+                    vec![action]
+                }
+            },
+        ));
+        store.apply_middleware(reporter);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let _sub = store.subscribe(move |state: &i32, action: &i32| {
+            log.borrow_mut().push((*state, *action));
+        });
+
+        store.dispatch(100);
+        let reported: Vec<i32> = reports.borrow().iter().map(|r| r.action).collect();
+        assert_eq!(reported, [-1, -2]);
+        assert_eq!(*seen.borrow(), [(3, 3)]);
+        assert_eq!(store.get_state(), 3);
+    }
+}
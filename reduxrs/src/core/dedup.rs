@@ -0,0 +1,183 @@
+use alloc::collections::{BTreeSet, VecDeque};
+use alloc::string::{String, ToString};
+
+/// 从 action 取唯一 id；None 表示不参与去重
+pub type ActionId<A> = fn(&A) -> Option<&str>;
+
+/// 去重窗口：记住最近 capacity 个 id（LRU，重复出现会刷新），窗口内重复的 action 被丢弃
+pub(crate) struct Dedup<A> {
+    id: ActionId<A>,
+    capacity: usize,
+    order: VecDeque<String>,
+    seen: BTreeSet<String>,
+}
+
+impl<A> Dedup<A> {
+    pub(crate) fn new(capacity: usize, id: ActionId<A>) -> Self {
+        assert!(
+            capacity > 0,
+            "Dedup window capacity must be greater than zero."
+        );
+        Self {
+            id,
+            capacity,
+            order: VecDeque::new(),
+            seen: BTreeSet::new(),
+        }
+    }
+
+    /// 窗口里已有这个 id（没有 id 的 action 永远不算重复）；重复出现会刷新它的位置
+    pub(crate) fn is_duplicate(&mut self, action: &A) -> bool {
+        let Some(id) = (self.id)(action) else {
+            return false;
+        };
+        if !self.seen.contains(id) {
+            return false;
+        }
+        if let Some(index) = self.order.iter().position(|seen| seen == id) {
+            let id = self.order.remove(index).expect("index is in bounds");
+            self.order.push_back(id);
+        }
+        true
+    }
+
+    /// action 被 reducer 接受之后记下它的 id；被 middleware 吞掉或被 reducer 拒绝的 action 不占用 id
+    pub(crate) fn record(&mut self, action: &A) {
+        let Some(id) = (self.id)(action) else {
+            return;
+        };
+        if self.seen.contains(id) {
+            return;
+        }
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.seen.remove(&oldest);
+        }
+        self.seen.insert(id.to_string());
+        self.order.push_back(id.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+    use core::cell::{Cell, RefCell};
+
+    use super::*;
+    use crate::core::middleware::{MiddlewareApi, filter_action};
+    use crate::core::store::Store;
+
+    fn id<'a>(action: &'a (&'static str, i32)) -> Option<&'a str> {
+        (!action.0.is_empty()).then_some(action.0)
+    }
+
+    // 模拟一次成功提交：不重复才记录
+    fn commit(dedup: &mut Dedup<(&'static str, i32)>, action: (&'static str, i32)) -> bool {
+        if dedup.is_duplicate(&action) {
+            return false;
+        }
+        dedup.record(&action);
+        true
+    }
+
+    #[test]
+    fn oldest_id_is_evicted_at_capacity() {
+        let mut dedup = Dedup::new(2, id);
+        assert!(commit(&mut dedup, ("a", 1)));
+        assert!(commit(&mut dedup, ("b", 1)));
+        assert!(!commit(&mut dedup, ("a", 2)));
+        assert!(commit(&mut dedup, ("c", 1)));
+        // a 刚被刷新过，被挤出去的是 b
+        assert!(!commit(&mut dedup, ("a", 3)));
+        assert!(commit(&mut dedup, ("b", 2)));
+        assert_eq!(dedup.order, ["a", "b"]);
+        assert_eq!(dedup.seen.len(), 2);
+    }
+
+    #[test]
+    fn actions_without_id_always_pass() {
+        let mut dedup = Dedup::new(1, id);
+        assert!(commit(&mut dedup, ("", 1)));
+        assert!(commit(&mut dedup, ("", 1)));
+        assert!(dedup.order.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Dedup window capacity must be greater than zero.")]
+    fn zero_capacity_is_rejected() {
+        Dedup::new(0, id);
+    }
+
+    #[test]
+    fn store_drops_redelivered_actions() {
+        let store = Store::new(
+            |state: &i32, action: &(&'static str, i32)| state + action.1,
+            0,
+        );
+        store.enable_dedup_with(2, id);
+        for action in [
+            ("a", 1),
+            ("a", 1),
+            ("b", 10),
+            ("", 100),
+            ("", 100),
+            ("c", 1000),
+        ] {
+            store.dispatch(action);
+        }
+        assert_eq!(store.get_state(), 1211);
+        // 窗口里只剩 b、c：a 被淘汰后再次投递会生效
+        store.dispatch(("a", 1));
+        store.dispatch(("c", 1000));
+        assert_eq!(store.get_state(), 1212);
+    }
+
+    #[test]
+    fn rejected_action_can_be_redelivered() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let sink = errors.clone();
+        let store = Store::new_fallible(
+            |state: &i32, action: &(&'static str, i32)| {
+                if *state + action.1 < 0 {
+                    Err("negative")
+                } else {
+                    Ok(state + action.1)
+                }
+            },
+            0,
+            move |_, error, _| sink.borrow_mut().push(error),
+        );
+        store.enable_dedup_with(4, id);
+        store.dispatch(("a", -1));
+        assert_eq!(*errors.borrow(), ["negative"]);
+        store.dispatch(("b", 5));
+        // 第一次被拒绝的 a 没有占用 id，重新投递时照常生效
+        store.dispatch(("a", -1));
+        store.dispatch(("a", -1));
+        assert_eq!(store.get_state(), 4);
+        assert_eq!(errors.borrow().len(), 1);
+    }
+
+    #[test]
+    fn swallowed_action_can_be_redelivered() {
+        let store = Store::new(
+            |state: &i32, action: &(&'static str, i32)| state + action.1,
+            0,
+        );
+        store.enable_dedup_with(4, id);
+        let first = Rc::new(Cell::new(true));
+        store.apply_middleware(filter_action(
+            move |_: &dyn MiddlewareApi<i32, (&'static str, i32)>, _: &(&'static str, i32)| {
+                // 第一次投递被吞掉
+                !first.replace(false)
+            },
+        ));
+        store.dispatch(("a", 10));
+        assert_eq!(store.get_state(), 0);
+        store.dispatch(("a", 10));
+        store.dispatch(("a", 10));
+        assert_eq!(store.get_state(), 10);
+    }
+}
@@ -0,0 +1,199 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+
+use super::enhancer::{StoreCreator, StoreEnhancer};
+use super::store::{PreviewFlag, Reducer};
+
+/// 派生数据（合计、索引、搜索结果）：包装 reducer，每次 reducer 之后取 inputs，
+/// 与上一次不同才调用 compute；结果（包括 memo 命中时的克隆）每次都通过 assign 写回 state，
+/// 因此 get_state / selector 直接读到的就是最新的派生值，reducer 重建 state 时也不会丢失
+/// D 较大时可以用 Rc 包一层，让克隆更便宜；replace_reducer 换上的新 reducer 同样经过这里
+pub fn derive_slice<S, A, I, D>(
+    inputs: impl Fn(&S) -> I + 'static,
+    compute: impl Fn(&I) -> D + 'static,
+    assign: impl Fn(&mut S, D) + 'static,
+) -> impl StoreEnhancer<S, A>
+where
+    S: 'static,
+    A: 'static,
+    I: PartialEq + 'static,
+    D: Clone + 'static,
+{
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        Box::new(move |reducer, preloaded_state| {
+            let last: RefCell<Option<(I, D)>> = RefCell::new(None);
+            let preview = PreviewFlag::new();
+            let previewing = preview.clone();
+            let refresh = Rc::new(move |mut state: S| {
+                let current = inputs(&state);
+                let mut last = last.borrow_mut();
+                let derived = match &*last {
+                    Some((inputs, derived)) if *inputs == current => derived.clone(),
+                    _ => {
+                        let derived = compute(&current);
+                        // preview 的结果不会提交，不更新 memo
+                        if !previewing.is_set() {
+                            *last = Some((current, derived.clone()));
+                        }
+                        derived
+                    }
+                };
+                assign(&mut state, derived);
+                state
+            });
+            let preloaded_state = refresh(preloaded_state);
+            let wrap = move |reducer: Box<Reducer<S, A>>| -> Box<Reducer<S, A>> {
+                let refresh = refresh.clone();
+                Box::new(move |state, action| refresh(reducer(state, action)))
+            };
+            let store = next(wrap(reducer), preloaded_state);
+            store.track_preview(&preview);
+            store.on_replace_reducer(wrap);
+            store
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::core::enhancer::create_store;
+    use crate::core::snapshot::StateSnapshot;
+    use crate::core::store::Store;
+    use crate::core::storet::InternalAction;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Cart {
+        items: Vec<i32>,
+        total: i32,
+    }
+
+    fn cart_store() -> Store<Cart, i32> {
+        create_store(
+            |cart: &Cart, item: &i32| {
+                let mut items = cart.items.clone();
+                items.push(*item);
+                Cart {
+                    items,
+                    ..cart.clone()
+                }
+            },
+            Cart {
+                items: vec![1],
+                total: 0,
+            },
+            derive_slice(
+                |cart: &Cart| cart.items.clone(),
+                |items: &Vec<i32>| items.iter().sum(),
+                |cart: &mut Cart, total| cart.total = total,
+            ),
+        )
+    }
+
+    #[test]
+    fn derived_value_follows_inputs() {
+        let store = cart_store();
+        assert_eq!(store.get_state().total, 1);
+        store.dispatch(2);
+        store.dispatch(3);
+        assert_eq!(store.get_state().total, 6);
+    }
+
+    #[test]
+    fn preview_does_not_update_memo() {
+        let store = cart_store();
+        assert_eq!(store.preview(&5).total, 6);
+        assert_eq!(store.get_state().total, 1);
+        store.dispatch(5);
+        assert_eq!(store.get_state().total, 6);
+    }
+
+    #[test]
+    fn replaced_reducer_keeps_derived_value() {
+        let store = cart_store();
+        // 新 reducer 每次放入两倍的值
+        store.replace_reducer(|cart: &Cart, item: &i32| {
+            let mut items = cart.items.clone();
+            items.push(item * 2);
+            Cart {
+                items,
+                ..cart.clone()
+            }
+        });
+        store.dispatch(2);
+        assert_eq!(store.get_state().total, 5);
+    }
+
+    enum Edit {
+        Set(Vec<i32>),
+        Internal,
+    }
+
+    impl From<InternalAction> for Edit {
+        fn from(_: InternalAction) -> Self {
+            Edit::Internal
+        }
+    }
+
+    #[test]
+    fn restored_state_gets_memoized_value_on_next_commit() {
+        let store = create_store(
+            |cart: &Cart, edit: &Edit| match edit {
+                Edit::Set(items) => Cart {
+                    items: items.clone(),
+                    ..cart.clone()
+                },
+                Edit::Internal => cart.clone(),
+            },
+            Cart {
+                items: vec![1],
+                total: 0,
+            },
+            derive_slice(
+                |cart: &Cart| cart.items.clone(),
+                |items: &Vec<i32>| items.iter().sum(),
+                |cart: &mut Cart, total| cart.total = total,
+            ),
+        );
+        store.restore(StateSnapshot::new(Cart {
+            items: vec![2, 3],
+            total: 5,
+        }));
+        // inputs 回到 restore 之前 memo 记住的值：restore 带来的 total 被 memo 覆盖
+        store.dispatch(Edit::Set(vec![1]));
+        assert_eq!(store.get_state().total, 1);
+    }
+
+    #[test]
+    fn memo_hit_still_assigns_after_reducer_rebuilds_state() {
+        // Set 之外的 action 不改 items，但重建了整个 state（total 回到 0）
+        let store = create_store(
+            |cart: &Cart, edit: &Edit| match edit {
+                Edit::Set(items) => Cart {
+                    items: items.clone(),
+                    ..cart.clone()
+                },
+                Edit::Internal => Cart {
+                    items: cart.items.clone(),
+                    total: 0,
+                },
+            },
+            Cart {
+                items: vec![1, 2],
+                total: 0,
+            },
+            derive_slice(
+                |cart: &Cart| cart.items.clone(),
+                |items: &Vec<i32>| items.iter().sum(),
+                |cart: &mut Cart, total| cart.total = total,
+            ),
+        );
+        assert_eq!(store.get_state().total, 3);
+        store.dispatch(Edit::Internal);
+        assert_eq!(store.get_state().total, 3);
+    }
+}
@@ -0,0 +1,524 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::mem::{self, Discriminant};
+use core::time::Duration;
+
+use super::enhancer::{StoreCreator, StoreEnhancer};
+use super::store::{PreviewFlag, Reducer};
+use super::storet::Action;
+use super::timer::Clock;
+
+/// diagnostics enhancer 发现的可疑用法
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// 单次 reducer 耗时超过阈值
+    SlowReducer {
+        action_type: String,
+        elapsed: Duration,
+    },
+    /// 同一个 type 字符串来自不同的来源（例如两个子 enum 都叫 "reset"，见 Diagnostics::action_source），
+    /// 按 type 匹配的 matcher / middleware 分不清它们
+    DuplicateActionType { action_type: String },
+    /// 连续 streak 次通知结束时都有新的 action 在排队：listener 可能在每次通知里 dispatch
+    DispatchLoop { action_type: String, streak: usize },
+    /// 连续 streak 次 commit 后 state 的大小都在增长（泄漏的启发式判断）
+    MonotonicGrowth { size: usize, streak: usize },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::SlowReducer {
+                action_type,
+                elapsed,
+            } => write!(f, "The reducer took {elapsed:?} to handle {action_type}."),
+            Diagnostic::DuplicateActionType { action_type } => write!(
+                f,
+                "The action type {action_type} is used by more than one action variant."
+            ),
+            Diagnostic::DispatchLoop {
+                action_type,
+                streak,
+            } => write!(
+                f,
+                "Listeners dispatched during {streak} notifications in a row (last action: {action_type}). This may be a dispatch loop."
+            ),
+            Diagnostic::MonotonicGrowth { size, streak } => write!(
+                f,
+                "The state has grown for {streak} commits in a row (size: {size}). This may be a leak."
+            ),
+        }
+    }
+}
+
+pub type DiagnosticSink = dyn Fn(&Diagnostic) + 'static;
+
+/// state 的“大小”（集合长度之和等），用于判断是否持续增长
+pub type StateSize<S> = fn(&S) -> usize;
+
+/// action 的来源（所属的子 enum、action creator 所在的模块等），用于发现重复的 type 字符串
+pub type ActionSource<A> = fn(&A) -> &'static str;
+
+/// diagnostics enhancer 的配置；默认只检查慢 reducer / 重复 type / dispatch 循环，
+/// state 增长需要通过 state_growth 提供 size
+pub struct Diagnostics<S, A> {
+    clock: Rc<dyn Clock>,
+    slow_reducer: Duration,
+    loop_streak: usize,
+    growth: Option<(StateSize<S>, usize)>,
+    source: Option<ActionSource<A>>,
+    sink: Rc<DiagnosticSink>,
+}
+
+impl<S, A> Diagnostics<S, A> {
+    /// 默认：reducer 超过 16ms（一帧）报告，连续 100 次通知都有嵌套 dispatch 报告，
+    /// 报告输出到 stderr（no_std 下忽略，需要 report_to）
+    pub fn new(clock: Rc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            slow_reducer: Duration::from_millis(16),
+            loop_streak: 100,
+            growth: None,
+            source: None,
+            sink: Rc::new(|_diagnostic: &Diagnostic| {
+                #[cfg(feature = "std")]
+                eprintln!("[reduxrs] {}", _diagnostic);
+            }),
+        }
+    }
+
+    pub fn slow_reducer(mut self, threshold: Duration) -> Self {
+        self.slow_reducer = threshold;
+        self
+    }
+
+    pub fn dispatch_loop(mut self, streak: usize) -> Self {
+        assert!(
+            streak > 0,
+            "Dispatch loop streak must be greater than zero."
+        );
+        self.loop_streak = streak;
+        self
+    }
+
+    /// 连续 streak 次 commit 严格增长时报告（不变不中断，减少则重新计数）
+    pub fn state_growth(mut self, size: StateSize<S>, streak: usize) -> Self {
+        assert!(streak > 0, "State growth streak must be greater than zero.");
+        self.growth = Some((size, streak));
+        self
+    }
+
+    /// 重复 type 按 source 区分来源：同一个 type 出现在两个不同的 source 上时报告
+    /// （例如 AppAction::Todos(_) => "todos"、AppAction::Users(_) => "users"；struct action 可以按 payload 类型区分）；
+    /// 默认只比较 A 最外层的 enum 变体
+    pub fn action_source(mut self, source: ActionSource<A>) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// 报告交给 sink；sink 可能在 reducer 执行期间被调用，不要在里面访问 store
+    pub fn report_to(mut self, sink: impl Fn(&Diagnostic) + 'static) -> Self {
+        self.sink = Rc::new(sink);
+        self
+    }
+}
+
+enum Source<A> {
+    Variant(Discriminant<A>),
+    Named(&'static str),
+}
+
+// derive 会要求 A: PartialEq，Discriminant 本身不需要
+impl<A> PartialEq for Source<A> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Source::Variant(a), Source::Variant(b)) => a == b,
+            (Source::Named(a), Source::Named(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+// reducer 包装里记录 type -> 第一次见到的来源
+struct TypeRegistry<A> {
+    source: Option<ActionSource<A>>,
+    sources: BTreeMap<String, Source<A>>,
+    reported: BTreeSet<String>,
+}
+
+impl<A: Action> TypeRegistry<A> {
+    // 第一次发现冲突时返回 true
+    fn check(&mut self, action: &A) -> bool {
+        let type_ = action.type_();
+        let source = match self.source {
+            Some(source) => Source::Named(source(action)),
+            None => Source::Variant(mem::discriminant(action)),
+        };
+        match self.sources.get(type_) {
+            None => {
+                self.sources.insert(type_.to_string(), source);
+                false
+            }
+            Some(first) if *first == source => false,
+            Some(_) => self.reported.insert(type_.to_string()),
+        }
+    }
+}
+
+/// 开发期诊断：慢 reducer、重复的 action type、listener 引起的 dispatch 循环、state 持续增长
+/// 只在 debug 构建（debug_assertions）里生效，release 构建原样返回 next；
+/// 重复 type 的来源见 Diagnostics::action_source；Store::preview 既不计时也不记录 type；
+/// replace_reducer 换上的新 reducer 同样计时
+pub fn diagnostics<S, A>(options: Diagnostics<S, A>) -> impl StoreEnhancer<S, A>
+where
+    S: Clone + 'static,
+    A: Action + 'static,
+{
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        if !cfg!(debug_assertions) {
+            return next;
+        }
+        Box::new(move |reducer, preloaded_state| {
+            let Diagnostics {
+                clock,
+                slow_reducer,
+                loop_streak,
+                growth,
+                source,
+                sink,
+            } = options;
+
+            let report = sink.clone();
+            let types = Rc::new(RefCell::new(TypeRegistry {
+                source,
+                sources: BTreeMap::new(),
+                reported: BTreeSet::new(),
+            }));
+            let preview = PreviewFlag::new();
+            let previewing = preview.clone();
+            let wrap = move |reducer: Box<Reducer<S, A>>| -> Box<Reducer<S, A>> {
+                let report = report.clone();
+                let types = types.clone();
+                let clock = clock.clone();
+                let previewing = previewing.clone();
+                Box::new(move |state, action| {
+                    // preview 的 action 不会提交，不算在诊断里
+                    if previewing.is_set() {
+                        return reducer(state, action);
+                    }
+                    if types.borrow_mut().check(action) {
+                        report(&Diagnostic::DuplicateActionType {
+                            action_type: action.type_().to_string(),
+                        });
+                    }
+                    let started = clock.now();
+                    let next_state = reducer(state, action);
+                    let elapsed = clock.now().saturating_sub(started);
+                    if elapsed > slow_reducer {
+                        report(&Diagnostic::SlowReducer {
+                            action_type: action.type_().to_string(),
+                            elapsed,
+                        });
+                    }
+                    next_state
+                })
+            };
+            let store = next(wrap(reducer), preloaded_state);
+            store.track_preview(&preview);
+            store.on_replace_reducer(wrap);
+
+            // 排在所有 listener 之后：此时还在排队的 action 来自本轮通知
+            let weak = store.downgrade();
+            let report = sink.clone();
+            let streak = Cell::new(0);
+            store
+                .subscribe_with_priority(i32::MAX, move |_, action: &A| {
+                    let queued = weak
+                        .upgrade()
+                        .is_some_and(|store| store.queued_dispatches() > 0);
+                    if !queued {
+                        streak.set(0);
+                        return;
+                    }
+                    streak.set(streak.get() + 1);
+                    if streak.get() == loop_streak {
+                        streak.set(0);
+                        report(&Diagnostic::DispatchLoop {
+                            action_type: action.type_().to_string(),
+                            streak: loop_streak,
+                        });
+                    }
+                })
+                .detach();
+
+            if let Some((size, threshold)) = growth {
+                let last = Cell::new(size(&store.get_state()));
+                let streak = Cell::new(0);
+                store.on_commit(move |state, _, _| {
+                    let current = size(state);
+                    let previous = last.replace(current);
+                    if current < previous {
+                        streak.set(0);
+                    } else if current > previous {
+                        streak.set(streak.get() + 1);
+                        if streak.get() == threshold {
+                            streak.set(0);
+                            sink(&Diagnostic::MonotonicGrowth {
+                                size: current,
+                                streak: threshold,
+                            });
+                        }
+                    }
+                });
+            }
+            store
+        })
+    }
+}
+
+// release 构建下诊断为空操作
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::core::enhancer::create_store;
+    use crate::core::store::Store;
+    use crate::core::timer::ManualTimer;
+
+    type Reports = Rc<RefCell<Vec<Diagnostic>>>;
+
+    #[derive(Clone, Debug)]
+    enum AppAction {
+        Todos(&'static str),
+        Users(&'static str),
+    }
+
+    impl Action for AppAction {
+        fn type_(&self) -> &str {
+            match self {
+                AppAction::Todos(type_) | AppAction::Users(type_) => type_,
+            }
+        }
+    }
+
+    // struct action：所有 action 的 discriminant 都相同，只能按 source 区分
+    #[derive(Clone, Debug)]
+    struct Named {
+        type_: &'static str,
+        module: &'static str,
+    }
+
+    impl Action for Named {
+        fn type_(&self) -> &str {
+            self.type_
+        }
+    }
+
+    fn diagnosed_with<A: Action + 'static>(
+        timer: &Rc<ManualTimer>,
+        reducer: impl Fn(&i32, &A) -> i32 + 'static,
+        options: impl FnOnce(Diagnostics<i32, A>) -> Diagnostics<i32, A>,
+    ) -> (Store<i32, A>, Reports) {
+        let reports: Reports = Rc::new(RefCell::new(Vec::new()));
+        let sink = reports.clone();
+        let options = options(Diagnostics::new(timer.clone()))
+            .report_to(move |diagnostic| sink.borrow_mut().push(diagnostic.clone()));
+        let store = create_store(reducer, 0, diagnostics(options));
+        (store, reports)
+    }
+
+    fn diagnosed<A: Action + 'static>(
+        options: impl FnOnce(Diagnostics<i32, A>) -> Diagnostics<i32, A>,
+    ) -> (Store<i32, A>, Reports) {
+        diagnosed_with(
+            &Rc::new(ManualTimer::new()),
+            |state: &i32, _: &A| state + 1,
+            options,
+        )
+    }
+
+    fn named(type_: &'static str) -> Named {
+        Named {
+            type_,
+            module: "app",
+        }
+    }
+
+    fn duplicate(action_type: &str) -> Diagnostic {
+        Diagnostic::DuplicateActionType {
+            action_type: action_type.to_string(),
+        }
+    }
+
+    #[test]
+    fn duplicate_type_across_variants_is_reported_once() {
+        let (store, reports) = diagnosed(|options| options);
+        store.dispatch(AppAction::Todos("reset"));
+        store.dispatch(AppAction::Todos("reset"));
+        assert!(reports.borrow().is_empty());
+        store.dispatch(AppAction::Users("reset"));
+        store.dispatch(AppAction::Users("reset"));
+        assert_eq!(*reports.borrow(), [duplicate("reset")]);
+    }
+
+    #[test]
+    fn action_source_separates_struct_actions() {
+        let todos = |type_| Named {
+            type_,
+            module: "todos",
+        };
+        let users = |type_| Named {
+            type_,
+            module: "users",
+        };
+
+        let (store, reports) = diagnosed(|options| options);
+        store.dispatch(todos("reset"));
+        store.dispatch(users("reset"));
+        assert!(reports.borrow().is_empty());
+
+        let (store, reports) =
+            diagnosed(|options| options.action_source(|action: &Named| action.module));
+        store.dispatch(todos("reset"));
+        store.dispatch(todos("reset"));
+        store.dispatch(users("added"));
+        assert!(reports.borrow().is_empty());
+        store.dispatch(users("reset"));
+        assert_eq!(*reports.borrow(), [duplicate("reset")]);
+    }
+
+    #[test]
+    fn action_source_replaces_variant_comparison() {
+        // 两个变体属于同一个来源时不算重复
+        let (store, reports) = diagnosed(|options| options.action_source(|_: &AppAction| "app"));
+        store.dispatch(AppAction::Todos("reset"));
+        store.dispatch(AppAction::Users("reset"));
+        assert!(reports.borrow().is_empty());
+        assert_eq!(store.get_state(), 2);
+    }
+
+    #[test]
+    fn slow_reducer_is_reported_with_its_elapsed_time() {
+        let timer = Rc::new(ManualTimer::new());
+        let clock = timer.clone();
+        let (store, reports) = diagnosed_with(
+            &timer,
+            move |state: &i32, action: &Named| {
+                if action.type_ == "slow" {
+                    clock.advance(Duration::from_millis(20));
+                }
+                state + 1
+            },
+            |options| options,
+        );
+        store.dispatch(named("fast"));
+        assert!(reports.borrow().is_empty());
+        store.dispatch(named("slow"));
+        assert_eq!(
+            *reports.borrow(),
+            [Diagnostic::SlowReducer {
+                action_type: "slow".to_string(),
+                elapsed: Duration::from_millis(20),
+            }]
+        );
+    }
+
+    #[test]
+    fn replaced_reducer_is_still_timed() {
+        let timer = Rc::new(ManualTimer::new());
+        let clock = timer.clone();
+        let (store, reports) = diagnosed_with(
+            &timer,
+            |state: &i32, _: &Named| state + 1,
+            |options| options,
+        );
+        store.replace_reducer(move |state: &i32, _: &Named| {
+            clock.advance(Duration::from_millis(20));
+            state + 1
+        });
+        store.dispatch(named("slow"));
+        assert_eq!(reports.borrow().len(), 1);
+    }
+
+    #[test]
+    fn preview_is_not_diagnosed() {
+        let timer = Rc::new(ManualTimer::new());
+        let clock = timer.clone();
+        let (store, reports) = diagnosed_with(
+            &timer,
+            move |state: &i32, action: &AppAction| {
+                if let AppAction::Users(_) = action {
+                    clock.advance(Duration::from_millis(20));
+                }
+                state + 1
+            },
+            |options| options,
+        );
+        // 慢且会与之后的 Todos("reset") 冲突，但没有提交
+        assert_eq!(store.preview(&AppAction::Users("reset")), 1);
+        store.dispatch(AppAction::Todos("reset"));
+        assert!(reports.borrow().is_empty());
+        store.dispatch(AppAction::Users("reset"));
+        assert_eq!(reports.borrow().len(), 2);
+        assert_eq!(reports.borrow()[0], duplicate("reset"));
+    }
+
+    #[test]
+    fn listener_dispatching_every_notification_is_reported() {
+        let (store, reports) = diagnosed(|options| options.dispatch_loop(3));
+        let inner = store.clone();
+        // 前三次通知都有嵌套 dispatch，第四次停下
+        let _sub = store.subscribe(move |state, _| {
+            if *state < 4 {
+                inner.dispatch(named("tick"));
+            }
+        });
+        store.dispatch(named("start"));
+        assert_eq!(store.get_state(), 4);
+        assert_eq!(
+            *reports.borrow(),
+            [Diagnostic::DispatchLoop {
+                action_type: "tick".to_string(),
+                streak: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn growth_streak_ignores_equal_and_resets_on_shrink() {
+        let timer = Rc::new(ManualTimer::new());
+        let (store, reports) = diagnosed_with(
+            &timer,
+            |state: &i32, action: &Named| match action.type_ {
+                "grow" => state + 1,
+                "shrink" => state - 1,
+                _ => *state,
+            },
+            |options| options.state_growth(|state: &i32| *state as usize, 3),
+        );
+        let dispatch_all = |types: &[&'static str]| {
+            for type_ in types {
+                store.dispatch(named(type_));
+            }
+        };
+        // 不变不中断
+        dispatch_all(&["grow", "grow", "same", "grow"]);
+        assert_eq!(
+            *reports.borrow(),
+            [Diagnostic::MonotonicGrowth { size: 3, streak: 3 }]
+        );
+        // 减少重新计数
+        dispatch_all(&["grow", "shrink", "grow", "grow"]);
+        assert_eq!(reports.borrow().len(), 1);
+        dispatch_all(&["grow"]);
+        assert_eq!(
+            reports.borrow()[1],
+            Diagnostic::MonotonicGrowth { size: 6, streak: 3 }
+        );
+    }
+}
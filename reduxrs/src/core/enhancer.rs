@@ -0,0 +1,226 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::fmt::Debug;
+
+use super::autobatch::BatchSchedule;
+#[cfg(feature = "std")]
+use super::invariant::OnViolation;
+use super::middleware::Middleware;
+use super::store::{Reducer, Store};
+use super::storet::Action;
+use super::timer::{Clock, Timer};
+
+/// 等价 Redux 的 createStore：(reducer, preloadedState) => store
+pub type StoreCreator<S, A> = Box<dyn FnOnce(Box<Reducer<S, A>>, S) -> Store<S, A>>;
+
+/// 等价 Redux 的 StoreEnhancer：createStore => createStore
+/// devtools / history / 持久化 / autobatch 等都以 enhancer 的形式叠加
+pub trait StoreEnhancer<S, A> {
+    fn enhance(self: Box<Self>, next: StoreCreator<S, A>) -> StoreCreator<S, A>;
+}
+
+impl<S, A, F> StoreEnhancer<S, A> for F
+where
+    F: FnOnce(StoreCreator<S, A>) -> StoreCreator<S, A>,
+{
+    fn enhance(self: Box<Self>, next: StoreCreator<S, A>) -> StoreCreator<S, A> {
+        (*self)(next)
+    }
+}
+
+/// 基础的 creator：直接 Store::new
+pub fn base_creator<S: 'static, A: 'static>() -> StoreCreator<S, A> {
+    Box::new(|reducer, preloaded_state| Store::new(reducer, preloaded_state))
+}
+
+/// 用 enhancer 创建 store（不需要 enhancer 时直接用 Store::new）
+pub fn create_store<S: 'static, A: 'static>(
+    reducer: impl Fn(&S, &A) -> S + 'static,
+    preloaded_state: S,
+    enhancer: impl StoreEnhancer<S, A> + 'static,
+) -> Store<S, A> {
+    let creator = Box::new(enhancer).enhance(base_creator());
+    creator(Box::new(reducer), preloaded_state)
+}
+
+/// 等价 compose(...enhancers)：第一个 enhancer 在最外层
+pub fn compose_enhancers<S: 'static, A: 'static>(
+    enhancers: Vec<Box<dyn StoreEnhancer<S, A>>>,
+) -> impl StoreEnhancer<S, A> {
+    move |next: StoreCreator<S, A>| {
+        enhancers
+            .into_iter()
+            .rev()
+            .fold(next, |creator, enhancer| enhancer.enhance(creator))
+    }
+}
+
+/// 等价 applyMiddleware(...middlewares)：按顺序装到新创建的 store 上，
+/// 排在内层 enhancer 装的 middleware 之前（与 Redux 包装 dispatch 的顺序一致）
+pub fn apply_middleware<S: 'static, A: 'static>(
+    middleware: Vec<Box<dyn Middleware<S, A>>>,
+) -> impl StoreEnhancer<S, A> {
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        Box::new(move |reducer, preloaded_state| {
+            let store = next(reducer, preloaded_state);
+            store.prepend_middleware(middleware.into_iter().map(Rc::from).collect());
+            store
+        })
+    }
+}
+
+/// 开发期不可变性检查（见 Store::enable_immutable_check）
+#[cfg(feature = "std")]
+pub fn immutable_check<S: Debug + 'static, A: 'static>(
+    on_violation: OnViolation,
+) -> impl StoreEnhancer<S, A> {
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        Box::new(move |reducer, preloaded_state| {
+            let store = next(reducer, preloaded_state);
+            store.enable_immutable_check(on_violation);
+            store
+        })
+    }
+}
+
+/// state 未变化时跳过通知（见 Store::enable_change_detection）
+pub fn change_detection<S: PartialEq + 'static, A: 'static>() -> impl StoreEnhancer<S, A> {
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        Box::new(move |reducer, preloaded_state| {
+            let store = next(reducer, preloaded_state);
+            store.enable_change_detection();
+            store
+        })
+    }
+}
+
+/// 按 Action::id 去重（见 Store::enable_dedup）
+pub fn dedup<S: 'static, A: Action + 'static>(capacity: usize) -> impl StoreEnhancer<S, A> {
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        Box::new(move |reducer, preloaded_state| {
+            let store = next(reducer, preloaded_state);
+            store.enable_dedup(capacity);
+            store
+        })
+    }
+}
+
+/// dispatch / reducer / listener 耗时统计（见 Store::enable_metrics）
+pub fn metrics<S: 'static, A: Action + 'static>(clock: Rc<dyn Clock>) -> impl StoreEnhancer<S, A> {
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        Box::new(move |reducer, preloaded_state| {
+            let store = next(reducer, preloaded_state);
+            store.enable_metrics(clock);
+            store
+        })
+    }
+}
+
+/// 注入定时器（见 Store::set_timer）；要放在依赖定时器的 enhancer（Timeout 的 auto_batch 等）之内
+pub fn timer<S: 'static, A: 'static>(timer: Rc<dyn Timer>) -> impl StoreEnhancer<S, A> {
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        Box::new(move |reducer, preloaded_state| {
+            let store = next(reducer, preloaded_state);
+            store.set_timer(timer);
+            store
+        })
+    }
+}
+
+/// 低优先级 action 合并通知（见 Store::enable_auto_batch）
+/// BatchSchedule::Timeout 时，内层需要有 timer enhancer
+pub fn auto_batch<S: 'static, A: 'static>(
+    is_low_priority: impl Fn(&A) -> bool + 'static,
+    schedule: BatchSchedule,
+) -> impl StoreEnhancer<S, A> {
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        Box::new(move |reducer, preloaded_state| {
+            let store = next(reducer, preloaded_state);
+            store.enable_auto_batch(is_low_priority, schedule);
+            store
+        })
+    }
+}
+
+/// 同上，按 Action::should_autobatch 判断
+pub fn auto_batch_actions<S: 'static, A: Action + 'static>(
+    schedule: BatchSchedule,
+) -> impl StoreEnhancer<S, A> {
+    auto_batch(|action: &A| action.should_autobatch(), schedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use core::cell::RefCell;
+
+    use super::*;
+    use crate::core::middleware::{MiddlewareApi, Next};
+
+    type Log = Rc<RefCell<Vec<&'static str>>>;
+
+    // 创建 store 前后各记一笔，看出包装的嵌套顺序
+    fn traced(
+        log: &Log,
+        enter: &'static str,
+        exit: &'static str,
+    ) -> Box<dyn StoreEnhancer<i32, i32>> {
+        let log = log.clone();
+        Box::new(
+            move |next: StoreCreator<i32, i32>| -> StoreCreator<i32, i32> {
+                Box::new(move |reducer, preloaded_state| {
+                    log.borrow_mut().push(enter);
+                    let store = next(reducer, preloaded_state);
+                    log.borrow_mut().push(exit);
+                    store
+                })
+            },
+        )
+    }
+
+    fn tagged(log: &Log, name: &'static str) -> Box<dyn Middleware<i32, i32>> {
+        let log = log.clone();
+        Box::new(
+            move |_: &dyn MiddlewareApi<i32, i32>, action: i32, next: Next<i32>| {
+                log.borrow_mut().push(name);
+                next(action);
+            },
+        )
+    }
+
+    #[test]
+    fn compose_wraps_right_to_left() {
+        let log: Log = Rc::new(RefCell::new(Vec::new()));
+        let enhancer = compose_enhancers(vec![
+            traced(&log, "enter a", "exit a"),
+            traced(&log, "enter b", "exit b"),
+            traced(&log, "enter c", "exit c"),
+        ]);
+        create_store(|state: &i32, action: &i32| state + action, 0, enhancer);
+        // compose(a, b, c)(createStore) == a(b(c(createStore)))
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                "enter a", "enter b", "enter c", "exit c", "exit b", "exit a"
+            ]
+        );
+    }
+
+    #[test]
+    fn outer_middleware_sees_action_first() {
+        let log: Log = Rc::new(RefCell::new(Vec::new()));
+        let enhancer = compose_enhancers(vec![
+            Box::new(apply_middleware(vec![
+                tagged(&log, "m1"),
+                tagged(&log, "m2"),
+            ])),
+            Box::new(apply_middleware(vec![tagged(&log, "m3")])),
+        ]);
+        let store = create_store(|state: &i32, action: &i32| state + action, 0, enhancer);
+        store.dispatch(1);
+        assert_eq!(*log.borrow(), vec!["m1", "m2", "m3"]);
+        assert_eq!(store.get_state(), 1);
+    }
+}
@@ -0,0 +1,103 @@
+use alloc::borrow::Cow;
+use core::any::Any;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::storet::Action;
+
+/// 通用的 Flux Standard Action 信封：{ type, payload, meta, error }
+/// 适合来自 websocket / 脚本层等动态来源的 action，不必为每种 action 写专门的 enum
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FsaAction<P, M = ()> {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub type_: Cow<'static, str>,
+    pub payload: P,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub meta: M,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub error: bool,
+}
+
+impl<P> FsaAction<P> {
+    pub fn new(type_: impl Into<Cow<'static, str>>, payload: P) -> Self {
+        Self {
+            type_: type_.into(),
+            payload,
+            meta: (),
+            error: false,
+        }
+    }
+}
+
+impl<P, M> FsaAction<P, M> {
+    pub fn with_meta<N>(self, meta: N) -> FsaAction<P, N> {
+        FsaAction {
+            type_: self.type_,
+            payload: self.payload,
+            meta,
+            error: self.error,
+        }
+    }
+
+    /// 标记 payload 是一个错误
+    pub fn error(mut self) -> Self {
+        self.error = true;
+        self
+    }
+}
+
+impl<P, M: 'static> Action for FsaAction<P, M> {
+    fn type_(&self) -> &str {
+        &self.type_
+    }
+
+    fn meta(&self) -> Option<&dyn Any> {
+        Some(&self.meta)
+    }
+
+    fn is_error(&self) -> bool {
+        self.error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_meta_and_error_reach_action_trait() {
+        let action = FsaAction::new("todos/add", 1).with_meta(7u8).error();
+        assert_eq!(action.type_(), "todos/add");
+        assert!(action.is_error());
+        assert_eq!(action.meta().unwrap().downcast_ref::<u8>(), Some(&7));
+
+        let plain = FsaAction::new("todos/add", 1);
+        assert!(!plain.is_error());
+        assert!(plain.meta().unwrap().is::<()>());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_uses_type_key_and_defaults_meta_and_error() {
+        use alloc::string::String;
+
+        let action = FsaAction::new("todos/add", 1).with_meta(String::from("m"));
+        let json = serde_json::to_value(&action).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "todos/add", "payload": 1, "meta": "m", "error": false })
+        );
+        assert_eq!(
+            serde_json::from_value::<FsaAction<i32, String>>(json).unwrap(),
+            action
+        );
+
+        let minimal: FsaAction<i32, Option<String>> =
+            serde_json::from_str(r#"{ "type": "todos/add", "payload": 2 }"#).unwrap();
+        assert_eq!(minimal.type_, "todos/add");
+        assert_eq!(minimal.meta, None);
+        assert!(!minimal.error);
+    }
+}
@@ -0,0 +1,150 @@
+use alloc::boxed::Box;
+use alloc::format;
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
+use std::hash::DefaultHasher;
+
+/// 检测到 reducer 之外的 state 修改时怎么处理
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnViolation {
+    Panic,
+    Log,
+}
+
+/// 状态指纹：相同指纹视为 state 未被修改
+pub type Fingerprint<S> = dyn Fn(&S) -> u64 + 'static;
+
+/// OnViolation::Log 的报告去向（见 Store::set_violation_report）
+pub type ViolationReport = dyn Fn(&str) + 'static;
+
+// 默认：打印到 stderr；no_std 下没有输出目标，只能忽略
+fn print_report(_msg: &str) {
+    #[cfg(feature = "std")]
+    eprintln!("{}", _msg);
+}
+
+/// 默认指纹：对 Debug 输出做哈希（需要 std；no_std 下请用自定义指纹）
+///（RefCell / Cell / Mutex 都实现了 Debug，能看到内部可变的部分；它们大多没有实现 Hash）
+#[cfg(feature = "std")]
+pub fn debug_fingerprint<S: Debug>(state: &S) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    format!("{:?}", state).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 等价 RTK 的 immutable-state-invariant：
+/// 每次 commit 后记录指纹，下一次 dispatch 前 / reducer 执行后再比对
+pub(crate) struct ImmutableCheck<S> {
+    fingerprint: Box<Fingerprint<S>>,
+    on_violation: OnViolation,
+    report: Box<ViolationReport>,
+    last: Option<u64>,
+}
+
+impl<S> ImmutableCheck<S> {
+    pub(crate) fn new(fingerprint: Box<Fingerprint<S>>, on_violation: OnViolation) -> Self {
+        Self {
+            fingerprint,
+            on_violation,
+            report: Box::new(print_report),
+            last: None,
+        }
+    }
+
+    pub(crate) fn set_report(&mut self, report: Box<ViolationReport>) {
+        self.report = report;
+    }
+
+    /// 记录当前 state 作为“已提交”的基准
+    pub(crate) fn record(&mut self, state: &S) {
+        self.last = Some((self.fingerprint)(state));
+    }
+
+    /// 与基准比较；where_ 描述发现问题的位置
+    /// 报告 / panic 之前以当前 state 为新基准：同一次修改只报告一次，panic 被捕获后 store 仍可使用
+    pub(crate) fn verify(&mut self, state: &S, where_: &str) {
+        let Some(last) = self.last else {
+            return;
+        };
+        let current = (self.fingerprint)(state);
+        if current == last {
+            return;
+        }
+        self.last = Some(current);
+        let msg = format!(
+            "A state mutation was detected {}. State may only be changed by returning a new value from the reducer.",
+            where_
+        );
+        match self.on_violation {
+            OnViolation::Panic => panic!("{}", msg),
+            OnViolation::Log => (self.report)(&msg),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std", debug_assertions))]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::rc::Rc;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::core::store::Store;
+
+    // hits 在 Rc<Cell> 里：listener 拿到的克隆与 store 里的 state 共享它
+    #[derive(Clone, Debug)]
+    struct State {
+        count: i32,
+        hits: Rc<Cell<i32>>,
+    }
+
+    fn store(on_violation: OnViolation) -> Store<State, i32> {
+        let store = Store::new(
+            |state: &State, action: &i32| State {
+                count: state.count + action,
+                hits: state.hits.clone(),
+            },
+            State {
+                count: 0,
+                hits: Rc::new(Cell::new(0)),
+            },
+        );
+        store.enable_immutable_check(on_violation);
+        // 在 reducer 之外修改 state
+        store
+            .subscribe(|state: &State, _| state.hits.set(state.hits.get() + 1))
+            .detach();
+        store
+    }
+
+    const MESSAGE: &str = "A state mutation was detected between dispatches. State may only be changed by returning a new value from the reducer.";
+
+    #[test]
+    fn panic_mode_rejects_the_next_dispatch_once() {
+        let store = store(OnViolation::Panic);
+        store.dispatch(1);
+        let payload = catch_unwind(AssertUnwindSafe(|| store.dispatch(1))).unwrap_err();
+        assert_eq!(payload.downcast_ref::<String>().unwrap(), MESSAGE);
+        assert_eq!(store.get_state().count, 1);
+        // 同一次修改只 panic 一次，之后照常 dispatch
+        store.dispatch(1);
+        assert_eq!(store.get_state().count, 2);
+    }
+
+    #[test]
+    fn log_mode_reports_and_keeps_dispatching() {
+        let store = store(OnViolation::Log);
+        let logged = Rc::new(RefCell::new(Vec::new()));
+        let sink = logged.clone();
+        store.set_violation_report(move |msg| sink.borrow_mut().push(msg.to_string()));
+        store.dispatch(1);
+        store.dispatch(1);
+        store.dispatch(1);
+        assert_eq!(store.get_state().count, 3);
+        assert_eq!(*logged.borrow(), [MESSAGE, MESSAGE]);
+    }
+}
@@ -0,0 +1,162 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::enhancer::{StoreCreator, StoreEnhancer};
+use super::store::Version;
+use super::timer::Clock;
+
+/// journal 里记录的事件
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum JournalEvent<S, A> {
+    /// 被 reducer 接受的 action
+    Action(A),
+    /// Store::restore 直接换上的 state（不经过 reducer，重放时原样替换）
+    Restore(S),
+}
+
+/// journal 里的一条记录：已提交的 action 或 restore
+/// 开启 serde feature 后可以直接序列化（JSON lines、sqlite 的一列等由 writer 决定）
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JournalEntry<S, A> {
+    pub event: JournalEvent<S, A>,
+    /// 提交之后 store 的 version
+    pub version: Version,
+    /// 提交时 Clock::now()
+    pub timestamp: Duration,
+}
+
+/// 只追加的写入端：文件、sqlite、远程服务……
+pub trait JournalWriter<S, A> {
+    fn append(&mut self, entry: JournalEntry<S, A>);
+}
+
+impl<S, A, F> JournalWriter<S, A> for F
+where
+    F: FnMut(JournalEntry<S, A>),
+{
+    fn append(&mut self, entry: JournalEntry<S, A>) {
+        self(entry)
+    }
+}
+
+/// 把每个被 reducer 接受的 action（见 Store::on_commit）连同 version、时间戳追加到 writer；
+/// restore 记为带完整 state 的 Restore 事件（见 Store::on_restore）
+pub fn journal<S, A>(
+    clock: Rc<dyn Clock>,
+    writer: impl JournalWriter<S, A> + 'static,
+) -> impl StoreEnhancer<S, A>
+where
+    S: Clone + 'static,
+    A: Clone + 'static,
+{
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        Box::new(move |reducer, preloaded_state| {
+            let store = next(reducer, preloaded_state);
+            let writer = RefCell::new(writer);
+            let record = Rc::new(move |event: JournalEvent<S, A>, version: Version| {
+                writer.borrow_mut().append(JournalEntry {
+                    event,
+                    version,
+                    timestamp: clock.now(),
+                });
+            });
+            let on_commit = record.clone();
+            store.on_commit(move |_, action, version| {
+                on_commit(JournalEvent::Action(action.clone()), version);
+            });
+            store.on_restore(move |state, version| {
+                record(JournalEvent::Restore(state.clone()), version);
+            });
+            store
+        })
+    }
+}
+
+/// 从 journal 重建 state：按顺序把 action 交给 reducer，遇到 Restore 直接换成记录的 state
+/// 传入的 initial 应与写 journal 时的初始 state 相同
+pub fn replay_journal<S, A>(
+    reducer: impl Fn(&S, &A) -> S,
+    initial: S,
+    entries: impl IntoIterator<Item = JournalEntry<S, A>>,
+) -> S {
+    entries
+        .into_iter()
+        .fold(initial, |state, entry| match entry.event {
+            JournalEvent::Action(action) => reducer(&state, &action),
+            JournalEvent::Restore(restored) => restored,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::core::enhancer::create_store;
+    use crate::core::snapshot::StateSnapshot;
+    use crate::core::store::Store;
+    use crate::core::storet::AppAction;
+    use crate::core::timer::ManualTimer;
+
+    type Entries = Rc<RefCell<Vec<JournalEntry<i32, AppAction<i32>>>>>;
+
+    fn add(state: &i32, action: &AppAction<i32>) -> i32 {
+        match action {
+            AppAction::Business(n) => state + n,
+            AppAction::Internal(_) => *state,
+        }
+    }
+
+    fn journaled(clock: Rc<ManualTimer>) -> (Store<i32, AppAction<i32>>, Entries) {
+        let entries: Entries = Rc::new(RefCell::new(Vec::new()));
+        let log = entries.clone();
+        let store = create_store(
+            add,
+            0,
+            journal(clock, move |entry| log.borrow_mut().push(entry)),
+        );
+        (store, entries)
+    }
+
+    #[test]
+    fn records_commits_with_version_and_time() {
+        let clock = Rc::new(ManualTimer::new());
+        let (store, entries) = journaled(clock.clone());
+        store.dispatch(AppAction::Business(2));
+        clock.advance(Duration::from_millis(10));
+        store.dispatch(AppAction::Business(3));
+
+        let entries = entries.borrow();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(
+            entries[1].event,
+            JournalEvent::Action(AppAction::Business(3))
+        ));
+        assert_eq!(entries[1].version, store.version());
+        assert_eq!(
+            entries[1].timestamp - entries[0].timestamp,
+            Duration::from_millis(10)
+        );
+    }
+
+    #[test]
+    fn replay_after_restore_matches_store() {
+        let (store, entries) = journaled(Rc::new(ManualTimer::new()));
+        store.dispatch(AppAction::Business(2));
+        store.restore(StateSnapshot::new(100));
+        store.dispatch(AppAction::Business(3));
+
+        let entries = entries.borrow().clone();
+        assert!(matches!(entries[1].event, JournalEvent::Restore(100)));
+        assert_eq!(entries[1].version, 2);
+        assert_eq!(replay_journal(add, 0, entries), store.get_state());
+        assert_eq!(store.get_state(), 103);
+    }
+}
@@ -0,0 +1,67 @@
+use super::storet::Action;
+
+/// type 完全相等
+pub fn type_is<A: Action>(type_: &'static str) -> impl Fn(&A) -> bool + 'static {
+    move |action| action.type_() == type_
+}
+
+/// type 以 prefix 开头，例如 "todos/" 匹配一个 slice 的全部 action
+pub fn type_prefix<A: Action>(prefix: &'static str) -> impl Fn(&A) -> bool + 'static {
+    move |action| action.type_().starts_with(prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    use super::*;
+    use crate::core::fsa::FsaAction;
+    use crate::core::store::Store;
+
+    fn action(type_: &'static str) -> FsaAction<i32> {
+        FsaAction::new(type_, 1)
+    }
+
+    #[test]
+    fn type_is_requires_exact_type() {
+        let matcher = type_is("todos/add");
+        assert!(matcher(&action("todos/add")));
+        assert!(!matcher(&action("todos/added")));
+        assert!(!matcher(&action("todos")));
+    }
+
+    #[test]
+    fn type_prefix_keeps_slice_boundary() {
+        let matcher = type_prefix("todos/");
+        assert!(matcher(&action("todos/add")));
+        assert!(matcher(&action("todos/")));
+        assert!(!matcher(&action("todosX")));
+        assert!(!matcher(&action("todos")));
+        assert!(!matcher(&action("filters/todos/add")));
+    }
+
+    #[test]
+    fn subscribe_to_only_notifies_matching_actions() {
+        let store = Store::new(
+            |count: &i32, action: &FsaAction<i32>| count + action.payload,
+            0,
+        );
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let _sub = store.subscribe_to(type_prefix("todos/"), move |count, action| {
+            log.borrow_mut().push((*count, action.type_.clone()));
+        });
+        store.dispatch(action("todos/add"));
+        store.dispatch(action("todosX"));
+        store.dispatch(action("todos/remove"));
+        // 不匹配的 action 照常进入 reducer
+        assert_eq!(store.get_state(), 3);
+        assert_eq!(
+            *seen.borrow(),
+            vec![(1, "todos/add".into()), (3, "todos/remove".into())]
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use super::store::ListenerId;
+use super::timer::Clock;
+
+/// 直方图各个桶的上界（含）；超过最后一个的计入最后一个额外的桶
+pub const HISTOGRAM_BOUNDS: [Duration; 6] = [
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+];
+
+/// 固定桶的耗时直方图
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    count: u64,
+    total: Duration,
+    max: Duration,
+    // 长度为 HISTOGRAM_BOUNDS.len() + 1
+    buckets: Vec<u64>,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+            buckets: vec![0; HISTOGRAM_BOUNDS.len() + 1],
+        }
+    }
+}
+
+impl Histogram {
+    pub fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+        let index = HISTOGRAM_BOUNDS
+            .iter()
+            .position(|bound| elapsed <= *bound)
+            .unwrap_or(HISTOGRAM_BOUNDS.len());
+        self.buckets[index] += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.count) {
+            Ok(0) => Duration::ZERO,
+            Ok(count) => self.total / count,
+            Err(_) => self.total.div_f64(self.count as f64),
+        }
+    }
+
+    /// (上界, 次数)；上界为 None 的是最后一个桶（超过所有 HISTOGRAM_BOUNDS）
+    pub fn buckets(&self) -> impl Iterator<Item = (Option<Duration>, u64)> + '_ {
+        self.buckets
+            .iter()
+            .enumerate()
+            .map(|(index, count)| (HISTOGRAM_BOUNDS.get(index).copied(), *count))
+    }
+}
+
+/// 某个 action type 的统计
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ActionMetrics {
+    /// 经过 reducer 的次数（包括被 fallible reducer 拒绝的）
+    pub count: u64,
+    pub reducer: Histogram,
+}
+
+/// Store::metrics 返回的快照
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StoreMetrics {
+    /// 按 action type 统计
+    pub actions: BTreeMap<String, ActionMetrics>,
+    /// 按 listener 统计（id 见 Subscription::id）
+    pub listeners: BTreeMap<ListenerId, Histogram>,
+    /// 嵌套 dispatch 队列出现过的最大长度（见 Store::dispatch）
+    pub max_queue_depth: usize,
+}
+
+/// 从 action 取统计用的 type
+pub type ActionLabel<A> = fn(&A) -> &str;
+
+// 挂在 store 上的收集器（见 Store::enable_metrics）
+pub(crate) struct MetricsCollector<A> {
+    clock: Rc<dyn Clock>,
+    label: ActionLabel<A>,
+    data: StoreMetrics,
+}
+
+impl<A> MetricsCollector<A> {
+    pub(crate) fn new(clock: Rc<dyn Clock>, label: ActionLabel<A>) -> Self {
+        Self {
+            clock,
+            label,
+            data: StoreMetrics::default(),
+        }
+    }
+
+    pub(crate) fn clock(&self) -> Rc<dyn Clock> {
+        self.clock.clone()
+    }
+
+    pub(crate) fn now(&self) -> Duration {
+        self.clock.now()
+    }
+
+    pub(crate) fn record_reducer(&mut self, action: &A, elapsed: Duration) {
+        let label = (self.label)(action);
+        let entry = match self.data.actions.get_mut(label) {
+            Some(entry) => entry,
+            None => self.data.actions.entry(label.to_string()).or_default(),
+        };
+        entry.count += 1;
+        entry.reducer.record(elapsed);
+    }
+
+    pub(crate) fn record_listener(&mut self, id: ListenerId, elapsed: Duration) {
+        self.data.listeners.entry(id).or_default().record(elapsed);
+    }
+
+    pub(crate) fn record_queue_depth(&mut self, depth: usize) {
+        self.data.max_queue_depth = self.data.max_queue_depth.max(depth);
+    }
+
+    pub(crate) fn snapshot(&self) -> StoreMetrics {
+        self.data.clone()
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.data = StoreMetrics::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::core::store::Store;
+    use crate::core::storet::{AppAction, CounterAction};
+    use crate::core::timer::ManualTimer;
+
+    const INC: AppAction<CounterAction> = AppAction::Business(CounterAction::Inc);
+    const DEC: AppAction<CounterAction> = AppAction::Business(CounterAction::Dec);
+
+    fn counter(timer: &Rc<ManualTimer>) -> Store<i32, AppAction<CounterAction>> {
+        // inc 在 reducer 里花掉 2ms
+        let clock = timer.clone();
+        let store = Store::new(
+            move |state: &i32, action: &AppAction<CounterAction>| match action {
+                AppAction::Business(CounterAction::Inc) => {
+                    clock.advance(Duration::from_millis(2));
+                    state + 1
+                }
+                AppAction::Business(CounterAction::Dec) => state - 1,
+                AppAction::Internal(_) => *state,
+            },
+            0,
+        );
+        store.enable_metrics(timer.clone());
+        store
+    }
+
+    #[test]
+    fn counts_and_times_each_action_type() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = counter(&timer);
+        store.dispatch(INC);
+        store.dispatch(INC);
+        store.dispatch(DEC);
+        let metrics = store.metrics().unwrap();
+        let inc = &metrics.actions["counter/inc"];
+        assert_eq!(inc.count, 2);
+        assert_eq!(inc.reducer.total(), Duration::from_millis(4));
+        assert_eq!(inc.reducer.mean(), Duration::from_millis(2));
+        assert_eq!(metrics.actions["counter/dec"].count, 1);
+        assert_eq!(metrics.actions.len(), 2);
+
+        store.reset_metrics();
+        assert_eq!(store.metrics(), Some(StoreMetrics::default()));
+    }
+
+    #[test]
+    fn histogram_bounds_are_inclusive() {
+        let mut histogram = Histogram::default();
+        histogram.record(Duration::from_micros(10));
+        histogram.record(Duration::from_micros(10) + Duration::from_nanos(1));
+        histogram.record(Duration::from_secs(1));
+        histogram.record(Duration::from_secs(2));
+        let buckets: Vec<_> = histogram.buckets().collect();
+        assert_eq!(buckets.len(), HISTOGRAM_BOUNDS.len() + 1);
+        assert_eq!(buckets[0], (Some(Duration::from_micros(10)), 1));
+        assert_eq!(buckets[1], (Some(Duration::from_micros(100)), 1));
+        assert_eq!(buckets[5], (Some(Duration::from_secs(1)), 1));
+        assert_eq!(buckets[6], (None, 1));
+        assert_eq!(histogram.count(), 4);
+        assert_eq!(histogram.max(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn listener_time_is_attributed_by_subscription_id() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = counter(&timer);
+        let clock = timer.clone();
+        let slow = store.subscribe(move |_, _| clock.advance(Duration::from_millis(5)));
+        let fast = store.subscribe(|_, _| {});
+        store.dispatch(DEC);
+        store.dispatch(DEC);
+        let listeners = store.metrics().unwrap().listeners;
+        assert_eq!(listeners[&slow.id()].count(), 2);
+        assert_eq!(listeners[&slow.id()].total(), Duration::from_millis(10));
+        assert_eq!(listeners[&fast.id()].count(), 2);
+        assert_eq!(listeners[&fast.id()].total(), Duration::ZERO);
+    }
+
+    #[test]
+    fn max_queue_depth_tracks_nested_dispatch() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = counter(&timer);
+        let inner = store.clone();
+        let fired = Cell::new(false);
+        let _sub = store.subscribe(move |_, _| {
+            if !fired.replace(true) {
+                for _ in 0..3 {
+                    inner.dispatch(DEC);
+                }
+            }
+        });
+        store.dispatch(INC);
+        let metrics = store.metrics().unwrap();
+        assert_eq!(metrics.max_queue_depth, 3);
+        assert_eq!(metrics.actions["counter/dec"].count, 3);
+        assert_eq!(store.get_state(), -2);
+    }
+}
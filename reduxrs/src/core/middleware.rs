@@ -0,0 +1,86 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+/// middleware 能看到的 store 接口（等价 Redux 的 MiddlewareAPI：{ getState, dispatch }）
+/// 由 Store 实现，测试里也可以换成 MockStore
+pub trait MiddlewareApi<S, A> {
+    fn get_state(&self) -> S;
+    /// 从链头重新 dispatch（当前这一轮 dispatch 结束后才处理，见 Store::dispatch）
+    fn dispatch(&self, action: A);
+}
+
+/// 下一环：调用它把 action 交给后面的 middleware（最终到 reducer + listeners）
+/// 传入的 action 可以是改写过的（补 meta 等）；可以调用多次（拆分）、不调用（吞掉），
+/// 也可以保存下来稍后再调用（debounce 等）。常见用法见 map_action / filter_action / split_action
+pub type Next<A> = Rc<dyn Fn(A)>;
+
+/// 等价 Redux 的 `store => next => action => ...`
+pub trait Middleware<S, A> {
+    fn handle(&self, api: &dyn MiddlewareApi<S, A>, action: A, next: Next<A>);
+}
+
+impl<S, A, F> Middleware<S, A> for F
+where
+    F: Fn(&dyn MiddlewareApi<S, A>, A, Next<A>),
+{
+    fn handle(&self, api: &dyn MiddlewareApi<S, A>, action: A, next: Next<A>) {
+        self(api, action, next)
+    }
+}
+
+/// middleware 链的快照：dispatch 期间新增的 middleware 不影响这一轮
+pub(crate) type Chain<S, A> = Rc<Vec<Rc<dyn Middleware<S, A>>>>;
+
+/// 依次经过 chain，最后交给 last
+pub(crate) fn run_chain<S: 'static, A: 'static>(
+    api: Rc<dyn MiddlewareApi<S, A>>,
+    chain: Chain<S, A>,
+    action: A,
+    last: Next<A>,
+) {
+    next_at(api, chain, 0, last)(action)
+}
+
+fn next_at<S: 'static, A: 'static>(
+    api: Rc<dyn MiddlewareApi<S, A>>,
+    chain: Chain<S, A>,
+    index: usize,
+    last: Next<A>,
+) -> Next<A> {
+    if index >= chain.len() {
+        return last;
+    }
+    Rc::new(move |action| {
+        let next = next_at(api.clone(), chain.clone(), index + 1, last.clone());
+        chain[index].handle(&*api, action, next)
+    })
+}
+
+/// 改写每个 action 后再交给下一环，例如统一补上时间戳、用户 id 等审计字段
+pub fn map_action<S, A>(
+    f: impl Fn(&dyn MiddlewareApi<S, A>, A) -> A + 'static,
+) -> impl Middleware<S, A> {
+    move |api: &dyn MiddlewareApi<S, A>, action: A, next: Next<A>| next(f(api, action))
+}
+
+/// predicate 为 false 的 action 被吞掉（不会到达 reducer 和 listeners）
+pub fn filter_action<S, A>(
+    predicate: impl Fn(&dyn MiddlewareApi<S, A>, &A) -> bool + 'static,
+) -> impl Middleware<S, A> {
+    move |api: &dyn MiddlewareApi<S, A>, action: A, next: Next<A>| {
+        if predicate(api, &action) {
+            next(action)
+        }
+    }
+}
+
+/// 一个 action 拆成若干个，按顺序依次交给下一环；返回空 Vec 等于吞掉
+pub fn split_action<S, A>(
+    f: impl Fn(&dyn MiddlewareApi<S, A>, A) -> Vec<A> + 'static,
+) -> impl Middleware<S, A> {
+    move |api: &dyn MiddlewareApi<S, A>, action: A, next: Next<A>| {
+        for action in f(api, action) {
+            next(action)
+        }
+    }
+}
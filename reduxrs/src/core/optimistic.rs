@@ -0,0 +1,249 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use super::enhancer::{StoreCreator, StoreEnhancer};
+use super::store::{PreviewFlag, Reducer};
+
+/// action 在乐观更新中的角色；同一次请求的三个阶段用同一个 request id 关联
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptimisticPhase {
+    /// 乐观 patch：立即生效，之后可能被回滚
+    Begin(String),
+    /// 请求成功：该请求的 patch 转为正式提交
+    Commit(String),
+    /// 请求失败：撤销该请求的 patch，其余 action 在撤销后的 state 上重放
+    Revert(String),
+    /// 普通 action
+    Untracked,
+}
+
+struct Tracker<S, A> {
+    // 第一个未完成的乐观 patch 之前的 state
+    base: Option<S>,
+    // base 之后的全部 action；Some(id) 表示尚未确认的 patch
+    log: Vec<(Option<String>, A)>,
+}
+
+impl<S, A> Tracker<S, A> {
+    fn has_pending(&self) -> bool {
+        self.log.iter().any(|(id, _)| id.is_some())
+    }
+
+    fn clear(&mut self) {
+        self.base = None;
+        self.log.clear();
+    }
+}
+
+/// 乐观更新：classify 决定每个 action 的角色
+/// Revert 时从 base 重放剩余 action，reducer 需要是纯函数（会被多次调用）
+/// restore 换上的 state 视为已确认：之前未完成的 patch 不再跟踪，之后的 Revert 不会回到 restore 之前
+pub fn optimistic<S, A>(
+    classify: impl Fn(&A) -> OptimisticPhase + 'static,
+) -> impl StoreEnhancer<S, A>
+where
+    S: Clone + 'static,
+    A: Clone + 'static,
+{
+    move |next: StoreCreator<S, A>| -> StoreCreator<S, A> {
+        Box::new(move |reducer, preloaded_state| {
+            let tracker = Rc::new(RefCell::new(Tracker {
+                base: None,
+                log: Vec::new(),
+            }));
+            let reset = tracker.clone();
+            let classify = Rc::new(classify);
+            let preview = PreviewFlag::new();
+            let previewing = preview.clone();
+            // replace_reducer 换上的 reducer 共用同一个 tracker，之后的 Revert 用新 reducer 重放
+            let wrap = move |reducer: Box<Reducer<S, A>>| -> Box<Reducer<S, A>> {
+                let classify = classify.clone();
+                let tracker = tracker.clone();
+                let previewing = previewing.clone();
+                Box::new(move |state, action| {
+                    let phase = classify(action);
+                    // preview 只计算结果，不改动 tracker
+                    let previewing = previewing.is_set();
+                    let mut tracker = tracker.borrow_mut();
+                    if let OptimisticPhase::Begin(id) = phase {
+                        if !previewing {
+                            if tracker.base.is_none() {
+                                tracker.base = Some(state.clone());
+                            }
+                            tracker.log.push((Some(id), action.clone()));
+                        }
+                        return reducer(state, action);
+                    }
+
+                    // Revert 时从 base 重放其余 action，其余情况直接在当前 state 上继续
+                    let replayed = match &phase {
+                        OptimisticPhase::Revert(id) => tracker.base.clone().map(|base| {
+                            tracker
+                                .log
+                                .iter()
+                                .filter(|entry| entry.0.as_ref() != Some(id))
+                                .fold(base, |state, (_, action)| reducer(&state, action))
+                        }),
+                        _ => None,
+                    };
+                    let next_state = reducer(replayed.as_ref().unwrap_or(state), action);
+                    if previewing {
+                        return next_state;
+                    }
+
+                    match phase {
+                        OptimisticPhase::Commit(id) => {
+                            for entry in tracker.log.iter_mut() {
+                                if entry.0.as_ref() == Some(&id) {
+                                    entry.0 = None;
+                                }
+                            }
+                        }
+                        OptimisticPhase::Revert(id) => {
+                            tracker.log.retain(|entry| entry.0.as_ref() != Some(&id));
+                        }
+                        _ => {}
+                    }
+                    if tracker.has_pending() {
+                        tracker.log.push((None, action.clone()));
+                    } else {
+                        // 没有未确认的 patch 了，不再需要重放
+                        tracker.clear();
+                    }
+                    next_state
+                })
+            };
+            let store = next(wrap(reducer), preloaded_state);
+            store.track_preview(&preview);
+            store.on_restore(move |_, _| reset.borrow_mut().clear());
+            store.on_replace_reducer(wrap);
+            store
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::core::enhancer::create_store;
+    use crate::core::snapshot::StateSnapshot;
+    use crate::core::store::Store;
+    use crate::core::storet::InternalAction;
+
+    #[derive(Clone, Debug)]
+    enum Op {
+        Begin(&'static str, i32),
+        Commit(&'static str),
+        Revert(&'static str),
+        Add(i32),
+        Internal,
+    }
+
+    impl From<InternalAction> for Op {
+        fn from(_: InternalAction) -> Self {
+            Op::Internal
+        }
+    }
+
+    fn optimistic_store() -> Store<i32, Op> {
+        create_store(
+            |state: &i32, op: &Op| match op {
+                Op::Begin(_, n) | Op::Add(n) => state + n,
+                Op::Commit(_) | Op::Revert(_) | Op::Internal => *state,
+            },
+            0,
+            optimistic(|op: &Op| match op {
+                Op::Begin(id, _) => OptimisticPhase::Begin(id.to_string()),
+                Op::Commit(id) => OptimisticPhase::Commit(id.to_string()),
+                Op::Revert(id) => OptimisticPhase::Revert(id.to_string()),
+                Op::Add(_) | Op::Internal => OptimisticPhase::Untracked,
+            }),
+        )
+    }
+
+    #[test]
+    fn revert_replays_remaining_actions() {
+        let store = optimistic_store();
+        store.dispatch(Op::Begin("r1", 100));
+        store.dispatch(Op::Add(1));
+        store.dispatch(Op::Begin("r2", 10));
+        store.dispatch(Op::Revert("r1"));
+        assert_eq!(store.get_state(), 11);
+    }
+
+    #[test]
+    fn committed_patch_survives_later_revert() {
+        let store = optimistic_store();
+        store.dispatch(Op::Begin("r1", 100));
+        store.dispatch(Op::Begin("r2", 10));
+        store.dispatch(Op::Commit("r1"));
+        store.dispatch(Op::Revert("r2"));
+        assert_eq!(store.get_state(), 100);
+    }
+
+    #[test]
+    fn revert_after_everything_committed_keeps_state() {
+        let store = optimistic_store();
+        store.dispatch(Op::Begin("r1", 100));
+        store.dispatch(Op::Commit("r1"));
+        store.dispatch(Op::Add(1));
+        store.dispatch(Op::Revert("r1"));
+        assert_eq!(store.get_state(), 101);
+    }
+
+    #[test]
+    fn revert_removes_every_patch_with_that_id() {
+        let store = optimistic_store();
+        store.dispatch(Op::Begin("r1", 100));
+        store.dispatch(Op::Begin("r1", 20));
+        store.dispatch(Op::Add(3));
+        store.dispatch(Op::Revert("r1"));
+        assert_eq!(store.get_state(), 3);
+    }
+
+    #[test]
+    fn preview_does_not_record_patches() {
+        let store = optimistic_store();
+        assert_eq!(store.preview(&Op::Begin("ghost", 7)), 7);
+        store.dispatch(Op::Begin("r1", 100));
+        assert_eq!(store.preview(&Op::Revert("r1")), 0);
+        assert_eq!(store.get_state(), 100);
+        store.dispatch(Op::Revert("r1"));
+        assert_eq!(store.get_state(), 0);
+    }
+
+    #[test]
+    fn revert_after_replace_reducer_replays_with_new_reducer() {
+        let store = optimistic_store();
+        store.dispatch(Op::Begin("r1", 100));
+        // 新 reducer 把每次增量翻倍
+        store.replace_reducer(|state: &i32, op: &Op| match op {
+            Op::Begin(_, n) | Op::Add(n) => state + n * 2,
+            Op::Commit(_) | Op::Revert(_) | Op::Internal => *state,
+        });
+        store.dispatch(Op::Add(1));
+        assert_eq!(store.get_state(), 102);
+        store.dispatch(Op::Revert("r1"));
+        assert_eq!(store.get_state(), 2);
+    }
+
+    #[test]
+    fn revert_after_restore_keeps_restored_state() {
+        let store = optimistic_store();
+        store.dispatch(Op::Begin("r1", 100));
+        store.restore(StateSnapshot::new(5));
+        store.dispatch(Op::Add(1));
+        store.dispatch(Op::Revert("r1"));
+        assert_eq!(store.get_state(), 6);
+
+        // restore 之后开始的 patch 照常回滚到 restore 的 state 上
+        store.dispatch(Op::Begin("r2", 10));
+        store.dispatch(Op::Revert("r2"));
+        assert_eq!(store.get_state(), 6);
+    }
+}
@@ -0,0 +1,407 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::store::Version;
+
+/// JSON Patch（RFC 6902）的一条操作；diff 只生成 add / remove / replace
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+impl PatchOp {
+    /// JSON Pointer，例如 `/todos/2/done`
+    pub fn path(&self) -> &str {
+        match self {
+            PatchOp::Add { path, .. }
+            | PatchOp::Remove { path }
+            | PatchOp::Replace { path, .. } => path,
+        }
+    }
+}
+
+/// prev -> next 的结构化 diff：对象按 key、数组按下标递归比较
+pub fn diff(prev: &Value, next: &Value) -> Vec<PatchOp> {
+    let mut patch = Vec::new();
+    diff_at(&mut String::new(), prev, next, &mut patch);
+    patch
+}
+
+fn diff_at(path: &mut String, prev: &Value, next: &Value, patch: &mut Vec<PatchOp>) {
+    match (prev, next) {
+        (Value::Object(prev), Value::Object(next)) => {
+            for (key, prev_value) in prev {
+                let len = push_segment(path, key);
+                match next.get(key) {
+                    Some(next_value) => diff_at(path, prev_value, next_value, patch),
+                    None => patch.push(PatchOp::Remove { path: path.clone() }),
+                }
+                path.truncate(len);
+            }
+            for (key, next_value) in next {
+                if !prev.contains_key(key) {
+                    let len = push_segment(path, key);
+                    patch.push(PatchOp::Add {
+                        path: path.clone(),
+                        value: next_value.clone(),
+                    });
+                    path.truncate(len);
+                }
+            }
+        }
+        (Value::Array(prev), Value::Array(next)) => {
+            for (index, (prev_value, next_value)) in prev.iter().zip(next).enumerate() {
+                let len = push_segment(path, &index.to_string());
+                diff_at(path, prev_value, next_value, patch);
+                path.truncate(len);
+            }
+            for (index, next_value) in next.iter().enumerate().skip(prev.len()) {
+                let len = push_segment(path, &index.to_string());
+                patch.push(PatchOp::Add {
+                    path: path.clone(),
+                    value: next_value.clone(),
+                });
+                path.truncate(len);
+            }
+            // 从尾部往前删，前面的下标保持有效
+            for index in (next.len()..prev.len()).rev() {
+                let len = push_segment(path, &index.to_string());
+                patch.push(PatchOp::Remove { path: path.clone() });
+                path.truncate(len);
+            }
+        }
+        _ if prev != next => patch.push(PatchOp::Replace {
+            path: path.clone(),
+            value: next.clone(),
+        }),
+        _ => {}
+    }
+}
+
+// 追加一段转义后的 JSON Pointer，返回追加前的长度
+fn push_segment(path: &mut String, segment: &str) -> usize {
+    let len = path.len();
+    path.push('/');
+    path.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    len
+}
+
+/// apply_patch 失败：path 不存在或不合法
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatchError {
+    pub path: String,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid patch path: {}", self.path)
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// 按顺序应用 patch（接收端 / 测试用）；出错时 value 可能已被部分修改
+pub fn apply_patch(value: &mut Value, patch: &[PatchOp]) -> Result<(), PatchError> {
+    for op in patch {
+        apply_op(value, op).ok_or_else(|| PatchError {
+            path: op.path().to_string(),
+        })?;
+    }
+    Ok(())
+}
+
+fn apply_op(root: &mut Value, op: &PatchOp) -> Option<()> {
+    let path = op.path();
+    if path.is_empty() {
+        match op {
+            PatchOp::Add { value, .. } | PatchOp::Replace { value, .. } => *root = value.clone(),
+            PatchOp::Remove { .. } => return None,
+        }
+        return Some(());
+    }
+    let (parent, last) = path.rsplit_once('/')?;
+    let last = last.replace("~1", "/").replace("~0", "~");
+    let parent = root.pointer_mut(parent)?;
+    match (parent, op) {
+        (Value::Object(map), PatchOp::Add { value, .. }) => {
+            map.insert(last, value.clone());
+        }
+        (Value::Object(map), PatchOp::Replace { value, .. }) => {
+            *map.get_mut(&last)? = value.clone();
+        }
+        (Value::Object(map), PatchOp::Remove { .. }) => {
+            map.remove(&last)?;
+        }
+        (Value::Array(items), PatchOp::Add { value, .. }) => {
+            let index = if last == "-" {
+                items.len()
+            } else {
+                last.parse().ok()?
+            };
+            if index > items.len() {
+                return None;
+            }
+            items.insert(index, value.clone());
+        }
+        (Value::Array(items), PatchOp::Replace { value, .. }) => {
+            *items.get_mut(last.parse::<usize>().ok()?)? = value.clone();
+        }
+        (Value::Array(items), PatchOp::Remove { .. }) => {
+            let index: usize = last.parse().ok()?;
+            if index >= items.len() {
+                return None;
+            }
+            items.remove(index);
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+// 同一个 store 的所有 patch listener 共用：每次通知只序列化、diff 一次
+pub(crate) struct PatchCache {
+    // 最后一次成功序列化的 state；None 表示订阅时就序列化失败
+    prev: Option<Value>,
+    version: Version,
+    patch: Rc<Result<Vec<PatchOp>, serde_json::Error>>,
+}
+
+impl PatchCache {
+    pub(crate) fn new(state: &impl Serialize, version: Version) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            prev: serde_json::to_value(state).ok(),
+            version,
+            patch: Rc::new(Ok(Vec::new())),
+        }))
+    }
+
+    // 序列化失败时保留 prev：下一个成功的 patch 相对 listener 最后看到的 state
+    fn update(&mut self, state: &impl Serialize) {
+        self.patch = Rc::new(serde_json::to_value(state).map(|next| {
+            let patch = match &self.prev {
+                Some(prev) => diff(prev, &next),
+                None => vec![PatchOp::Replace {
+                    path: String::new(),
+                    value: next.clone(),
+                }],
+            };
+            self.prev = Some(next);
+            patch
+        }));
+    }
+}
+
+// 把 patch listener 包装成 versioned listener：同一个 version 只计算一次
+pub(crate) fn patch_listener<S: Serialize, A>(
+    cache: Rc<RefCell<PatchCache>>,
+    mut listener: impl FnMut(Result<&[PatchOp], &serde_json::Error>, &A) + 'static,
+) -> impl FnMut(&S, &A, Version) + 'static {
+    move |state, action, version| {
+        let patch = {
+            let mut cache = cache.borrow_mut();
+            if cache.version != version {
+                cache.update(state);
+                cache.version = version;
+            }
+            cache.patch.clone()
+        };
+        listener(patch.as_ref().as_ref().map(Vec::as_slice), action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::core::store::{Store, Subscription};
+
+    fn round_trip(prev: Value, next: Value) {
+        let patch = diff(&prev, &next);
+        let mut value = prev;
+        apply_patch(&mut value, &patch).unwrap();
+        assert_eq!(value, next);
+    }
+
+    #[test]
+    fn diff_of_equal_values_is_empty() {
+        let value = json!({"a": [1, {"b": null}]});
+        assert!(diff(&value, &value).is_empty());
+    }
+
+    #[test]
+    fn object_changes_round_trip() {
+        let prev = json!({"keep": 1, "change": {"x": 1}, "drop": true});
+        let next = json!({"keep": 1, "change": {"x": 2, "y": 3}, "add": "new"});
+        assert_eq!(
+            diff(&prev, &next),
+            vec![
+                PatchOp::Replace {
+                    path: "/change/x".to_string(),
+                    value: json!(2),
+                },
+                PatchOp::Add {
+                    path: "/change/y".to_string(),
+                    value: json!(3),
+                },
+                PatchOp::Remove {
+                    path: "/drop".to_string(),
+                },
+                PatchOp::Add {
+                    path: "/add".to_string(),
+                    value: json!("new"),
+                },
+            ]
+        );
+        round_trip(prev, next);
+    }
+
+    #[test]
+    fn array_growth_and_shrink_round_trip() {
+        round_trip(json!([1, 2]), json!([1, 5, 3, 4]));
+        round_trip(json!([1, 2, 3, 4]), json!([0]));
+        round_trip(json!({"list": []}), json!({"list": [{"id": 1}]}));
+    }
+
+    #[test]
+    fn escaped_keys_round_trip() {
+        let prev = json!({"a/b": 1, "c~d": {"e": 1}});
+        let next = json!({"a/b": 2, "c~d": {"e": 2}});
+        let patch = diff(&prev, &next);
+        assert_eq!(patch[0].path(), "/a~1b");
+        assert_eq!(patch[1].path(), "/c~0d/e");
+        round_trip(prev, next);
+    }
+
+    #[test]
+    fn type_change_replaces_whole_value() {
+        round_trip(json!({"a": [1]}), json!({"a": {"0": 1}}));
+        round_trip(json!(1), json!("root"));
+    }
+
+    #[test]
+    fn invalid_path_is_reported() {
+        let mut value = json!({"a": 1});
+        let error = apply_patch(
+            &mut value,
+            &[PatchOp::Remove {
+                path: "/missing/x".to_string(),
+            }],
+        )
+        .unwrap_err();
+        assert_eq!(error.path, "/missing/x");
+    }
+
+    fn push_store() -> Store<Vec<i32>, i32> {
+        Store::new(
+            |state: &Vec<i32>, item: &i32| {
+                let mut next = state.clone();
+                next.push(*item);
+                next
+            },
+            vec![1],
+        )
+    }
+
+    #[test]
+    fn listener_patches_rebuild_store_state() {
+        let store = push_store();
+        let mirror = Rc::new(RefCell::new(json!(store.get_state())));
+        let target = mirror.clone();
+        let _sub = store.subscribe_patches(move |patch, _| {
+            apply_patch(&mut target.borrow_mut(), patch.unwrap()).unwrap();
+        });
+        store.dispatch(2);
+        store.dispatch(3);
+        assert_eq!(*mirror.borrow(), json!(store.get_state()));
+    }
+
+    #[test]
+    fn resubscribing_starts_from_current_state() {
+        let store = push_store();
+        let first = store.subscribe_patches(|_, _| {});
+        store.dispatch(2);
+        first.unsubscribe();
+        store.dispatch(3);
+
+        let mirror = Rc::new(RefCell::new(json!(store.get_state())));
+        let target = mirror.clone();
+        let _second = store.subscribe_patches(move |patch, _| {
+            apply_patch(&mut target.borrow_mut(), patch.unwrap()).unwrap();
+        });
+        store.dispatch(4);
+        assert_eq!(*mirror.borrow(), json!([1, 2, 3, 4]));
+    }
+
+    // 负数无法序列化
+    #[derive(Clone)]
+    struct Balance(i32);
+
+    impl Serialize for Balance {
+        fn serialize<Ser: serde::Serializer>(
+            &self,
+            serializer: Ser,
+        ) -> Result<Ser::Ok, Ser::Error> {
+            if self.0 < 0 {
+                return Err(serde::ser::Error::custom("negative balance"));
+            }
+            serializer.serialize_i32(self.0)
+        }
+    }
+
+    type Delivered = Rc<RefCell<Vec<Result<Vec<PatchOp>, String>>>>;
+
+    fn balance_store(initial: i32) -> (Store<Balance, i32>, Delivered, Subscription) {
+        let store = Store::new(
+            |state: &Balance, delta: &i32| Balance(state.0 + delta),
+            Balance(initial),
+        );
+        let seen: Delivered = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let sub = store.subscribe_patches(move |patch, _| {
+            log.borrow_mut()
+                .push(patch.map(<[PatchOp]>::to_vec).map_err(|e| e.to_string()));
+        });
+        (store, seen, sub)
+    }
+
+    fn replace(path: &str, value: i32) -> PatchOp {
+        PatchOp::Replace {
+            path: path.to_string(),
+            value: json!(value),
+        }
+    }
+
+    #[test]
+    fn serialization_error_is_delivered_to_listener() {
+        let (store, seen, _sub) = balance_store(1);
+        store.dispatch(-5);
+        store.dispatch(10);
+        // 恢复后的 patch 相对最后一次成功序列化的 state
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                Err("negative balance".to_string()),
+                Ok(vec![replace("", 6)])
+            ]
+        );
+    }
+
+    #[test]
+    fn unserializable_state_at_subscribe_time_starts_with_full_replace() {
+        let (store, seen, _sub) = balance_store(-1);
+        store.dispatch(3);
+        store.dispatch(1);
+        assert_eq!(
+            *seen.borrow(),
+            vec![Ok(vec![replace("", 2)]), Ok(vec![replace("", 3)])]
+        );
+    }
+}
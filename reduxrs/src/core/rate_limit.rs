@@ -0,0 +1,282 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use core::cell::RefCell;
+use core::time::Duration;
+
+use super::middleware::{Middleware, MiddlewareApi, Next};
+use super::storet::Action;
+use super::timer::{Timer, TimerId};
+
+/// 在窗口的哪一端放行
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edges {
+    pub leading: bool,
+    pub trailing: bool,
+}
+
+impl Edges {
+    pub const LEADING: Edges = Edges {
+        leading: true,
+        trailing: false,
+    };
+    pub const TRAILING: Edges = Edges {
+        leading: false,
+        trailing: true,
+    };
+    pub const BOTH: Edges = Edges {
+        leading: true,
+        trailing: true,
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Debounce,
+    Throttle,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Rule {
+    mode: Mode,
+    window: Duration,
+    edges: Edges,
+}
+
+struct Slot<A> {
+    timer: Option<TimerId>,
+    // 窗口结束时要放行的最后一个 action
+    pending: Option<(A, Next<A>)>,
+}
+
+type Slots<A> = Rc<RefCell<BTreeMap<String, Slot<A>>>>;
+
+/// 按 action type 配置 debounce / throttle 的 middleware
+/// 没有配置的 type 原样放行；延后放行的 action 直接交给 next，前面的 middleware 不会再看到一次
+pub struct RateLimit<A> {
+    timer: Rc<dyn Timer>,
+    rules: BTreeMap<String, Rule>,
+    slots: Slots<A>,
+}
+
+impl<A: 'static> RateLimit<A> {
+    pub fn new(timer: Rc<dyn Timer>) -> Self {
+        Self {
+            timer,
+            rules: BTreeMap::new(),
+            slots: Rc::new(RefCell::new(BTreeMap::new())),
+        }
+    }
+
+    /// 连续的 action 之间间隔不足 wait 时合并（lodash debounce 语义）
+    pub fn debounce(self, type_: impl Into<String>, wait: Duration, edges: Edges) -> Self {
+        self.rule(type_, Mode::Debounce, wait, edges)
+    }
+
+    /// 每个 window 最多放行一次 leading、一次 trailing（lodash throttle 语义）
+    pub fn throttle(self, type_: impl Into<String>, window: Duration, edges: Edges) -> Self {
+        self.rule(type_, Mode::Throttle, window, edges)
+    }
+
+    fn rule(
+        mut self,
+        type_: impl Into<String>,
+        mode: Mode,
+        window: Duration,
+        edges: Edges,
+    ) -> Self {
+        self.rules.insert(
+            type_.into(),
+            Rule {
+                mode,
+                window,
+                edges,
+            },
+        );
+        self
+    }
+}
+
+impl<S, A: Action + 'static> Middleware<S, A> for RateLimit<A> {
+    fn handle(&self, _api: &dyn MiddlewareApi<S, A>, action: A, next: Next<A>) {
+        let Some(&rule) = self.rules.get(action.type_()) else {
+            return next(action);
+        };
+        let key = action.type_().to_string();
+
+        let release_now = {
+            let mut slots = self.slots.borrow_mut();
+            let slot = slots.entry(key.clone()).or_insert(Slot {
+                timer: None,
+                pending: None,
+            });
+            let in_window = slot.timer.is_some();
+
+            if rule.mode == Mode::Debounce {
+                // 每次都重新计时
+                if let Some(id) = slot.timer.take() {
+                    self.timer.clear_timeout(id);
+                }
+                slot.timer = Some(schedule(&self.timer, &self.slots, key, rule));
+            } else if !in_window {
+                slot.timer = Some(schedule(&self.timer, &self.slots, key, rule));
+            }
+
+            if !in_window && rule.edges.leading {
+                slot.pending = None;
+                Some((action, next))
+            } else {
+                if rule.edges.trailing {
+                    slot.pending = Some((action, next));
+                }
+                None
+            }
+        };
+
+        if let Some((action, next)) = release_now {
+            next(action);
+        }
+    }
+}
+
+// 窗口结束：放行 trailing；throttle 放行之后开启新的窗口
+fn schedule<A: 'static>(
+    timer: &Rc<dyn Timer>,
+    slots: &Slots<A>,
+    key: String,
+    rule: Rule,
+) -> TimerId {
+    let (timer_handle, slots_handle) = (timer.clone(), slots.clone());
+    timer.set_timeout(
+        rule.window,
+        Box::new(move || {
+            let released = {
+                let mut slots = slots_handle.borrow_mut();
+                let Some(slot) = slots.get_mut(&key) else {
+                    return;
+                };
+                slot.timer = None;
+                let released = slot.pending.take();
+                if released.is_some() && rule.mode == Mode::Throttle {
+                    slot.timer = Some(schedule(&timer_handle, &slots_handle, key, rule));
+                }
+                released
+            };
+            if let Some((action, next)) = released {
+                next(action);
+            }
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::core::store::Store;
+    use crate::core::timer::ManualTimer;
+
+    #[derive(Clone, Debug)]
+    struct Add(&'static str, i32);
+
+    impl Action for Add {
+        fn type_(&self) -> &str {
+            self.0
+        }
+    }
+
+    type Committed = Rc<RefCell<Vec<i32>>>;
+
+    // 返回 store、定时器和按提交顺序记录的 payload
+    fn limited(
+        configure: impl FnOnce(RateLimit<Add>) -> RateLimit<Add>,
+    ) -> (Store<i32, Add>, Rc<ManualTimer>, Committed) {
+        let store = Store::new(|state: &i32, action: &Add| state + action.1, 0);
+        let timer = Rc::new(ManualTimer::new());
+        store.apply_middleware(configure(RateLimit::new(timer.clone())));
+        let committed = Rc::new(RefCell::new(Vec::new()));
+        let log = committed.clone();
+        store.on_commit(move |_, action, _| log.borrow_mut().push(action.1));
+        (store, timer, committed)
+    }
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn unconfigured_types_pass_through() {
+        let (store, _timer, committed) =
+            limited(|limit| limit.debounce("a", ms(10), Edges::TRAILING));
+        store.dispatch(Add("b", 1));
+        store.dispatch(Add("b", 2));
+        assert_eq!(*committed.borrow(), vec![1, 2]);
+    }
+
+    #[test]
+    fn debounce_trailing_releases_last_action_after_quiet_period() {
+        let (store, timer, committed) =
+            limited(|limit| limit.debounce("a", ms(10), Edges::TRAILING));
+        store.dispatch(Add("a", 1));
+        timer.advance(ms(5));
+        store.dispatch(Add("a", 2));
+        timer.advance(ms(5));
+        assert!(committed.borrow().is_empty());
+        timer.advance(ms(5));
+        assert_eq!(*committed.borrow(), vec![2]);
+        assert_eq!(store.get_state(), 2);
+    }
+
+    #[test]
+    fn debounce_leading_drops_actions_until_quiet() {
+        let (store, timer, committed) =
+            limited(|limit| limit.debounce("a", ms(10), Edges::LEADING));
+        store.dispatch(Add("a", 1));
+        timer.advance(ms(5));
+        store.dispatch(Add("a", 2));
+        timer.advance(ms(10));
+        store.dispatch(Add("a", 3));
+        assert_eq!(*committed.borrow(), vec![1, 3]);
+    }
+
+    #[test]
+    fn throttle_releases_leading_and_trailing_per_window() {
+        let (store, timer, committed) = limited(|limit| limit.throttle("a", ms(10), Edges::BOTH));
+        store.dispatch(Add("a", 1));
+        timer.advance(ms(3));
+        store.dispatch(Add("a", 2));
+        timer.advance(ms(3));
+        store.dispatch(Add("a", 3));
+        timer.advance(ms(4));
+        assert_eq!(*committed.borrow(), vec![1, 3]);
+        // trailing 放行后开启了新的窗口
+        timer.advance(ms(2));
+        store.dispatch(Add("a", 4));
+        timer.advance(ms(8));
+        assert_eq!(*committed.borrow(), vec![1, 3, 4]);
+        timer.advance(ms(10));
+        store.dispatch(Add("a", 5));
+        assert_eq!(*committed.borrow(), vec![1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn trailing_release_queues_nested_dispatch() {
+        let (store, timer, _) = limited(|limit| limit.debounce("x", ms(10), Edges::TRAILING));
+        let inner = store.clone();
+        let _first = store.subscribe(move |_, action| {
+            if action.0 == "x" {
+                inner.dispatch(Add("y", 100));
+            }
+        });
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let _second =
+            store.subscribe(move |state, action| log.borrow_mut().push((*state, action.0)));
+        store.dispatch(Add("x", 1));
+        timer.advance(ms(10));
+        assert_eq!(*seen.borrow(), vec![(1, "x"), (101, "y")]);
+    }
+}
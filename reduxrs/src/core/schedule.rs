@@ -0,0 +1,286 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::cell::{Cell, RefCell};
+use core::time::Duration;
+
+use super::store::Store;
+use super::timer::{Timer, TimerId};
+
+/// Store::dispatch_after / dispatch_every 返回的句柄
+/// Drop 不会取消（与 setTimeout / setInterval 一样），需要取消时调用 cancel
+#[derive(Clone)]
+pub struct ScheduleHandle {
+    state: Rc<ScheduleState>,
+}
+
+struct ScheduleState {
+    timer: Rc<dyn Timer>,
+    // 每次到期调用；返回 false 时不再继续（store 已经被 drop）
+    fire: Rc<dyn Fn() -> bool>,
+    // dispatch_every 的间隔；dispatch_after 为 None
+    interval: Option<Duration>,
+    // 当前挂在定时器上的回调；dispatch_every 每次触发后换成下一次的 id
+    pending: Cell<Option<TimerId>>,
+    // 已取消、dispatch_after 已触发或 store 已被 drop：不会再安排
+    stopped: Cell<bool>,
+}
+
+impl ScheduleHandle {
+    fn new(timer: Rc<dyn Timer>, fire: Rc<dyn Fn() -> bool>, interval: Option<Duration>) -> Self {
+        Self {
+            state: Rc::new(ScheduleState {
+                timer,
+                fire,
+                interval,
+                pending: Cell::new(None),
+                stopped: Cell::new(false),
+            }),
+        }
+    }
+
+    /// 取消尚未触发的 dispatch；已经 dispatch 过的不受影响
+    pub fn cancel(&self) {
+        self.state.stopped.set(true);
+        clear(&self.state);
+    }
+
+    /// 还会再 dispatch（dispatch_after 触发之后为 false）
+    pub fn is_active(&self) -> bool {
+        self.state.pending.get().is_some()
+    }
+
+    /// 每次 store.restore 之后按恢复出来的 state 重新安排：delay 返回 Some(d) 时从现在起 d 之后
+    /// dispatch（dispatch_every 从那时起重新按 interval 计时），返回 None 时暂停到下一次 restore
+    /// 已经 cancel、已经触发过的 dispatch_after 不会重新安排
+    pub fn reschedule_on_restore<S: 'static, A: 'static>(
+        &self,
+        store: &Store<S, A>,
+        delay: impl Fn(&S) -> Option<Duration> + 'static,
+    ) {
+        // hook 无法移除，只持有 Weak
+        let state = Rc::downgrade(&self.state);
+        store.on_restore(move |restored, _| {
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            if state.stopped.get() {
+                return;
+            }
+            clear(&state);
+            if let Some(delay) = delay(restored) {
+                arm(state, delay);
+            }
+        });
+    }
+}
+
+fn clear(state: &ScheduleState) {
+    if let Some(id) = state.pending.take() {
+        state.timer.clear_timeout(id);
+    }
+}
+
+// delay 之后调用一次 fire
+pub(crate) fn after(
+    timer: Rc<dyn Timer>,
+    delay: Duration,
+    fire: Box<dyn FnOnce()>,
+) -> ScheduleHandle {
+    let fire = RefCell::new(Some(fire));
+    let handle = ScheduleHandle::new(
+        timer,
+        Rc::new(move || {
+            if let Some(fire) = fire.borrow_mut().take() {
+                fire();
+            }
+            true
+        }),
+        None,
+    );
+    arm(handle.state.clone(), delay);
+    handle
+}
+
+// 每隔 interval 调用一次 fire，直到取消或 fire 返回 false（store 已经被 drop）
+pub(crate) fn every(
+    timer: Rc<dyn Timer>,
+    interval: Duration,
+    fire: Rc<dyn Fn() -> bool>,
+) -> ScheduleHandle {
+    assert!(
+        !interval.is_zero(),
+        "dispatch_every requires a non-zero interval."
+    );
+    let handle = ScheduleHandle::new(timer, fire, Some(interval));
+    arm(handle.state.clone(), interval);
+    handle
+}
+
+fn arm(state: Rc<ScheduleState>, delay: Duration) {
+    let next = state.clone();
+    let id = state.timer.set_timeout(
+        delay,
+        Box::new(move || {
+            next.pending.set(None);
+            // fire 里可能调用 cancel
+            let alive = (next.fire)();
+            match next.interval {
+                Some(interval) if alive && !next.stopped.get() => arm(next, interval),
+                _ => next.stopped.set(true),
+            }
+        }),
+    );
+    state.pending.set(Some(id));
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::core::snapshot::StateSnapshot;
+    use crate::core::storet::AppAction;
+    use crate::core::timer::ManualTimer;
+
+    const TICK: Duration = Duration::from_millis(10);
+
+    // state 记录收到的所有业务 action
+    fn store(timer: &Rc<ManualTimer>) -> Store<Vec<i32>, AppAction<i32>> {
+        let store = Store::new(
+            |state: &Vec<i32>, action: &AppAction<i32>| match action {
+                AppAction::Business(n) => {
+                    let mut next = state.clone();
+                    next.push(*n);
+                    next
+                }
+                AppAction::Internal(_) => state.clone(),
+            },
+            Vec::new(),
+        );
+        store.set_timer(timer.clone());
+        store
+    }
+
+    #[test]
+    fn dispatch_after_fires_once() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = store(&timer);
+        let handle = store.dispatch_after(TICK * 2, AppAction::Business(1));
+        timer.advance(TICK);
+        assert!(store.get_state().is_empty());
+        assert!(handle.is_active());
+        timer.advance(TICK * 5);
+        assert_eq!(store.get_state(), vec![1]);
+        assert!(!handle.is_active());
+        assert_eq!(timer.pending(), 0);
+    }
+
+    #[test]
+    fn dispatch_every_repeats_until_cancelled() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = store(&timer);
+        let handle = store.dispatch_every(TICK, AppAction::Business(1));
+        timer.advance(TICK * 3);
+        assert_eq!(store.get_state(), vec![1, 1, 1]);
+        handle.cancel();
+        assert!(!handle.is_active());
+        timer.advance(TICK * 3);
+        assert_eq!(store.get_state(), vec![1, 1, 1]);
+        assert_eq!(timer.pending(), 0);
+    }
+
+    #[test]
+    fn cancel_before_dispatch_after_fires() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = store(&timer);
+        store.dispatch_after(TICK, AppAction::Business(1)).cancel();
+        timer.advance(TICK * 2);
+        assert!(store.get_state().is_empty());
+        assert_eq!(timer.pending(), 0);
+    }
+
+    #[test]
+    fn cancel_from_listener_stops_dispatch_every() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = store(&timer);
+        let handle = store.dispatch_every(TICK, AppAction::Business(1));
+        let stop = handle.clone();
+        let _sub = store.subscribe(move |state: &Vec<i32>, _| {
+            if state.len() == 2 {
+                stop.cancel();
+            }
+        });
+        timer.advance(TICK * 5);
+        assert_eq!(store.get_state(), vec![1, 1]);
+        assert!(!handle.is_active());
+    }
+
+    #[test]
+    fn dropped_store_stops_schedules() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = store(&timer);
+        let once = store.dispatch_after(TICK, AppAction::Business(1));
+        let every = store.dispatch_every(TICK, AppAction::Business(2));
+        drop(store);
+        timer.advance(TICK);
+        // 到期时发现 store 已经不在：不 dispatch，也不再安排下一次
+        assert!(!once.is_active());
+        assert!(!every.is_active());
+        assert_eq!(timer.pending(), 0);
+    }
+
+    fn restore(store: &Store<Vec<i32>, AppAction<i32>>, state: Vec<i32>) {
+        store.restore(StateSnapshot::new(state));
+    }
+
+    #[test]
+    fn reschedule_on_restore_rearms_from_restored_state() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = store(&timer);
+        let handle = store.dispatch_every(TICK * 10, AppAction::Business(1));
+        // 恢复出来的 state 里已有几个 action，就过几个 TICK 再开始；为空时暂停
+        handle.reschedule_on_restore(&store, |state: &Vec<i32>| {
+            (!state.is_empty()).then(|| TICK * state.len() as u32)
+        });
+        timer.advance(TICK * 5);
+        restore(&store, vec![7, 7]);
+        timer.advance(TICK * 2);
+        assert_eq!(store.get_state(), vec![7, 7, 1]);
+        // 之后按 interval 继续
+        timer.advance(TICK * 10);
+        assert_eq!(store.get_state(), vec![7, 7, 1, 1]);
+
+        restore(&store, Vec::new());
+        assert!(!handle.is_active());
+        timer.advance(TICK * 50);
+        assert!(store.get_state().is_empty());
+
+        restore(&store, vec![7]);
+        timer.advance(TICK);
+        assert_eq!(store.get_state(), vec![7, 1]);
+    }
+
+    #[test]
+    fn reschedule_on_restore_skips_cancelled_and_fired_schedules() {
+        let timer = Rc::new(ManualTimer::new());
+        let store = store(&timer);
+        let cancelled = store.dispatch_every(TICK, AppAction::Business(1));
+        cancelled.reschedule_on_restore(&store, |_: &Vec<i32>| Some(TICK));
+        cancelled.cancel();
+        let pending = store.dispatch_after(TICK * 10, AppAction::Business(2));
+        pending.reschedule_on_restore(&store, |_: &Vec<i32>| Some(TICK));
+        let fired = store.dispatch_after(TICK, AppAction::Business(3));
+        fired.reschedule_on_restore(&store, |_: &Vec<i32>| Some(TICK));
+        timer.advance(TICK);
+        assert_eq!(store.get_state(), vec![3]);
+
+        restore(&store, Vec::new());
+        assert!(!cancelled.is_active());
+        assert!(pending.is_active());
+        timer.advance(TICK * 20);
+        // 未触发的 dispatch_after 按新的 delay 提前触发，且只触发一次
+        assert_eq!(store.get_state(), vec![2]);
+        assert_eq!(timer.pending(), 0);
+    }
+}
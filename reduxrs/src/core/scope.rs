@@ -0,0 +1,364 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+
+use super::store::{Store, Subscription};
+use super::storet::{InternalAction, InternalActionType};
+
+type ScopedListener<S, A> = Box<dyn FnMut(&S, &A)>;
+type Subscribe<S, A> = dyn Fn(ScopedListener<S, A>) -> Subscription + 'static;
+type Extract<P, A> = Rc<dyn Fn(&P) -> Option<A>>;
+
+/// 子 store：只看得到父 state 的一个投影，dispatch 的子 action 会映射回父 action
+/// 由 Store::scope / ScopedStore::scope 创建；自己不持有 state，
+/// 也没有自己的 middleware：子 action 经 embed 之后走父 store 的 middleware 链
+///
+/// 父 store 每次通知时子 listener 按下面的规则收到通知：
+/// - 父 action 能 extract 成子 action：总是通知，收到 (投影, 子 action)
+/// - 认不出（其他模块的 action、replace_reducer、父 store 的 @@redux/RESTORE 等）但投影变了：
+///   收到 (新投影, @@redux/RESTORE)，即子 state 被子 action 之外的途径替换
+/// - 认不出且投影没变：不通知
+pub struct ScopedStore<S, A> {
+    project: Rc<dyn Fn() -> S>,
+    dispatch: Rc<dyn Fn(A)>,
+    preview: Rc<dyn Fn(A) -> S>,
+    subscribe: Rc<Subscribe<S, A>>,
+}
+
+impl<S, A> Clone for ScopedStore<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            project: self.project.clone(),
+            dispatch: self.dispatch.clone(),
+            preview: self.preview.clone(),
+            subscribe: self.subscribe.clone(),
+        }
+    }
+}
+
+// 把父 listener 收到的 (state, action) 按上面的规则转给子 listener；last 是子 listener 最后看到的投影
+fn project_listener<P, PA, S, A>(
+    lens: Rc<dyn Fn(&P) -> S>,
+    extract: Extract<PA, A>,
+    mut last: S,
+    mut listener: ScopedListener<S, A>,
+) -> impl FnMut(&P, &PA) + 'static
+where
+    P: 'static,
+    PA: 'static,
+    S: PartialEq + 'static,
+    A: From<InternalAction> + 'static,
+{
+    move |state, action| {
+        let projected = lens(state);
+        let action = match extract(action) {
+            Some(action) => action,
+            None if projected != last => A::from(InternalAction {
+                kind: InternalActionType::Restore,
+            }),
+            None => return,
+        };
+        last = projected;
+        listener(&last, &action);
+    }
+}
+
+impl<S: PartialEq + 'static, A: From<InternalAction> + 'static> ScopedStore<S, A> {
+    pub(crate) fn from_store<P, PA>(
+        parent: &Store<P, PA>,
+        lens: impl Fn(&P) -> S + 'static,
+        embed: impl Fn(A) -> PA + 'static,
+        extract: impl Fn(&PA) -> Option<A> + 'static,
+    ) -> Self
+    where
+        P: Clone + 'static,
+        PA: 'static,
+    {
+        let lens: Rc<dyn Fn(&P) -> S> = Rc::new(lens);
+        let embed = Rc::new(embed);
+        let extract: Extract<PA, A> = Rc::new(extract);
+        let (project_parent, project_lens) = (parent.clone(), lens.clone());
+        let (dispatch_parent, dispatch_embed) = (parent.clone(), embed.clone());
+        let (preview_parent, preview_lens) = (parent.clone(), lens.clone());
+        let subscribe_parent = parent.clone();
+
+        Self {
+            // 在 borrow 内直接投影，不克隆整个父 state
+            project: Rc::new(move || project_parent.with_state(|state| project_lens(state))),
+            dispatch: Rc::new(move |action| dispatch_parent.dispatch(dispatch_embed(action))),
+            preview: Rc::new(move |action| preview_lens(&preview_parent.preview(&embed(action)))),
+            subscribe: Rc::new(move |listener| {
+                let initial = subscribe_parent.with_state(|state| lens(state));
+                subscribe_parent.subscribe(project_listener(
+                    lens.clone(),
+                    extract.clone(),
+                    initial,
+                    listener,
+                ))
+            }),
+        }
+    }
+
+    pub fn get_state(&self) -> S {
+        (self.project)()
+    }
+
+    /// 子 action 经 embed 映射成父 action 后 dispatch
+    pub fn dispatch(&self, action: A) {
+        (self.dispatch)(action)
+    }
+
+    /// 见 Store::preview：返回父 store 预览之后的投影
+    pub fn preview(&self, action: &A) -> S
+    where
+        A: Clone,
+    {
+        (self.preview)(action.clone())
+    }
+
+    /// listener 收到 (投影后的 state, 子 action)，通知规则见 ScopedStore
+    pub fn subscribe(&self, listener: impl FnMut(&S, &A) + 'static) -> Subscription {
+        (self.subscribe)(Box::new(listener))
+    }
+
+    /// 继续往下 scope
+    pub fn scope<T, B>(
+        &self,
+        lens: impl Fn(&S) -> T + 'static,
+        embed: impl Fn(B) -> A + 'static,
+        extract: impl Fn(&A) -> Option<B> + 'static,
+    ) -> ScopedStore<T, B>
+    where
+        T: PartialEq + 'static,
+        B: From<InternalAction> + 'static,
+    {
+        let lens: Rc<dyn Fn(&S) -> T> = Rc::new(lens);
+        let embed = Rc::new(embed);
+        let extract: Extract<A, B> = Rc::new(extract);
+        let (project_parent, project_lens) = (self.project.clone(), lens.clone());
+        let (dispatch_parent, dispatch_embed) = (self.dispatch.clone(), embed.clone());
+        let (preview_parent, preview_lens) = (self.preview.clone(), lens.clone());
+        let (subscribe_project, subscribe_parent) = (self.project.clone(), self.subscribe.clone());
+
+        ScopedStore {
+            project: Rc::new(move || project_lens(&project_parent())),
+            dispatch: Rc::new(move |action| dispatch_parent(dispatch_embed(action))),
+            preview: Rc::new(move |action| preview_lens(&preview_parent(embed(action)))),
+            subscribe: Rc::new(move |listener| {
+                let initial = lens(&subscribe_project());
+                subscribe_parent(Box::new(project_listener(
+                    lens.clone(),
+                    extract.clone(),
+                    initial,
+                    listener,
+                )))
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    use super::*;
+    use crate::core::snapshot::StateSnapshot;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct App {
+        todos: Todos,
+        clicks: i32,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Todos {
+        items: Vec<i32>,
+        limit: i32,
+    }
+
+    #[derive(Clone, Debug)]
+    enum Root {
+        Restore,
+        Todos(TodoAction),
+        Click,
+        ClearAll,
+    }
+
+    #[derive(Clone, Debug)]
+    enum TodoAction {
+        Restore,
+        Add(i32),
+        Limit(LimitAction),
+    }
+
+    #[derive(Clone, Debug)]
+    enum LimitAction {
+        Restore,
+        Set(i32),
+    }
+
+    impl From<InternalAction> for Root {
+        fn from(_: InternalAction) -> Self {
+            Root::Restore
+        }
+    }
+
+    impl From<InternalAction> for TodoAction {
+        fn from(_: InternalAction) -> Self {
+            TodoAction::Restore
+        }
+    }
+
+    impl From<InternalAction> for LimitAction {
+        fn from(_: InternalAction) -> Self {
+            LimitAction::Restore
+        }
+    }
+
+    type Seen = Rc<RefCell<Vec<(Todos, String)>>>;
+
+    // 子 listener 看到的 action，用字符串记录方便比较
+    fn label(action: &TodoAction) -> String {
+        match action {
+            TodoAction::Restore => "restore".into(),
+            TodoAction::Add(n) => format!("add {n}"),
+            TodoAction::Limit(LimitAction::Set(n)) => format!("limit {n}"),
+            TodoAction::Limit(LimitAction::Restore) => "limit restore".into(),
+        }
+    }
+
+    fn reduce(state: &App, action: &Root) -> App {
+        let mut next = state.clone();
+        match action {
+            Root::Todos(TodoAction::Add(n)) => next.todos.items.push(*n),
+            Root::Todos(TodoAction::Limit(LimitAction::Set(n))) => next.todos.limit = *n,
+            Root::Click => next.clicks += 1,
+            Root::ClearAll => next.todos.items.clear(),
+            Root::Restore | Root::Todos(_) => {}
+        }
+        next
+    }
+
+    fn app() -> Store<App, Root> {
+        Store::new(
+            reduce,
+            App {
+                todos: Todos {
+                    items: vec![1],
+                    limit: 10,
+                },
+                clicks: 0,
+            },
+        )
+    }
+
+    fn todos(store: &Store<App, Root>) -> ScopedStore<Todos, TodoAction> {
+        store.scope(
+            |app: &App| app.todos.clone(),
+            Root::Todos,
+            |action: &Root| match action {
+                Root::Todos(action) => Some(action.clone()),
+                _ => None,
+            },
+        )
+    }
+
+    fn record(scoped: &ScopedStore<Todos, TodoAction>) -> (Seen, Subscription) {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let sub = scoped.subscribe(move |todos, action| {
+            log.borrow_mut().push((todos.clone(), label(action)));
+        });
+        (seen, sub)
+    }
+
+    #[test]
+    fn projects_parent_state_and_embeds_dispatch() {
+        let store = app();
+        let scoped = todos(&store);
+        assert_eq!(scoped.get_state().items, vec![1]);
+        scoped.dispatch(TodoAction::Add(2));
+        assert_eq!(store.get_state().todos.items, vec![1, 2]);
+        assert_eq!(scoped.get_state().items, vec![1, 2]);
+        assert_eq!(scoped.preview(&TodoAction::Add(3)).items, vec![1, 2, 3]);
+        assert_eq!(scoped.get_state().items, vec![1, 2]);
+    }
+
+    #[test]
+    fn notifies_extracted_actions_and_outside_changes_only() {
+        let store = app();
+        let scoped = todos(&store);
+        let (seen, _sub) = record(&scoped);
+        scoped.dispatch(TodoAction::Add(2));
+        // 其他模块的 action，投影不变：不通知
+        store.dispatch(Root::Click);
+        // 其他模块的 action 改了投影：以 RESTORE 通知
+        store.dispatch(Root::ClearAll);
+        let labels: Vec<_> = seen
+            .borrow()
+            .iter()
+            .map(|(_, label)| label.clone())
+            .collect();
+        assert_eq!(labels, ["add 2", "restore"]);
+        assert!(seen.borrow()[1].0.items.is_empty());
+    }
+
+    #[test]
+    fn parent_restore_and_replace_reducer_reach_the_child() {
+        let store = app();
+        let scoped = todos(&store);
+        let (seen, _sub) = record(&scoped);
+        let before = store.snapshot();
+        scoped.dispatch(TodoAction::Add(2));
+        store.restore(before);
+        store.replace_reducer(|state: &App, _: &Root| App {
+            todos: Todos {
+                items: vec![],
+                limit: 0,
+            },
+            ..state.clone()
+        });
+        store.dispatch(Root::Click);
+        let seen = seen.borrow();
+        let items: Vec<_> = seen.iter().map(|(todos, _)| todos.items.clone()).collect();
+        let labels: Vec<_> = seen.iter().map(|(_, label)| label.as_str()).collect();
+        assert_eq!(items, [vec![1, 2], vec![1], vec![]]);
+        assert_eq!(labels, ["add 2", "restore", "restore"]);
+    }
+
+    #[test]
+    fn nested_scope_projects_dispatches_and_follows_outside_changes() {
+        let store = app();
+        let limit = todos(&store).scope(
+            |todos: &Todos| todos.limit,
+            TodoAction::Limit,
+            |action: &TodoAction| match action {
+                TodoAction::Limit(action) => Some(action.clone()),
+                _ => None,
+            },
+        );
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let _sub = limit.subscribe(move |limit, action| {
+            log.borrow_mut()
+                .push((*limit, matches!(action, LimitAction::Restore)));
+        });
+        assert_eq!(limit.get_state(), 10);
+        limit.dispatch(LimitAction::Set(3));
+        assert_eq!(store.get_state().todos.limit, 3);
+        assert_eq!(limit.preview(&LimitAction::Set(4)), 4);
+        // 兄弟 action：父 scope 会收到，但 limit 不变，不通知
+        store.dispatch(Root::Todos(TodoAction::Add(2)));
+        store.restore(StateSnapshot::new(App {
+            todos: Todos {
+                items: vec![],
+                limit: 7,
+            },
+            clicks: 0,
+        }));
+        assert_eq!(*seen.borrow(), vec![(3, false), (7, true)]);
+    }
+}
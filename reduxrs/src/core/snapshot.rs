@@ -0,0 +1,25 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::store::Version;
+
+/// 整个 store 的 state 快照：存档、崩溃恢复、测试 fixture
+/// 开启 serde feature 后可以直接序列化
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StateSnapshot<S> {
+    pub state: S,
+    /// 导出时 store 的 version；手工构造的快照为 0
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub version: Version,
+}
+
+impl<S> StateSnapshot<S> {
+    pub fn new(state: S) -> Self {
+        Self { state, version: 0 }
+    }
+
+    pub fn into_state(self) -> S {
+        self.state
+    }
+}
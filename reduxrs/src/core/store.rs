@@ -1,47 +1,365 @@
-use std::cell::RefCell;
-use std::collections::BTreeMap;
-use std::rc::{Rc, Weak};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::rc::{Rc, Weak};
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::{Cell, RefCell};
+use core::fmt;
+#[cfg(feature = "std")]
+use core::fmt::Debug;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::panic::{self, AssertUnwindSafe};
+
+use super::autobatch::{AutoBatch, BatchSchedule};
+use super::dedup::{ActionId, Dedup};
+#[cfg(feature = "std")]
+use super::invariant::debug_fingerprint;
+use super::invariant::{ImmutableCheck, OnViolation};
+use super::metrics::{ActionLabel, MetricsCollector, StoreMetrics};
+use super::middleware::{Chain, Middleware, MiddlewareApi, run_chain};
+#[cfg(feature = "patch")]
+use super::patch::{PatchCache, PatchOp, patch_listener};
+use super::schedule::{self, ScheduleHandle};
+use super::scope::ScopedStore;
+use super::snapshot::StateSnapshot;
+use super::storet::{Action, InternalAction, InternalActionType};
+use super::timer::{Clock, Timer};
+use super::watch::Watched;
 
 pub type ListenerId = u64;
 
+/// 通知顺序：数值小的先通知，同优先级按订阅顺序
+/// subscribe 使用 0；基础设施（持久化、devtools）可以用负数排在 UI 之前，或用正数排在之后
+pub type ListenerPriority = i32;
+
+// BTreeMap 的 key 顺序就是通知顺序
+pub(crate) type ListenerKey = (ListenerPriority, ListenerId);
+
 pub type Reducer<S, A> = dyn Fn(&S, &A) -> S + 'static;
 
+// 内部统一的 reducer：None 表示 fallible reducer 拒绝了这个 action
+type ReduceFn<S, A> = dyn Fn(&S, &A) -> Option<S> + 'static;
+
+/// 包装 reducer 的 enhancer 交给 Store::on_replace_reducer，replace_reducer 换上的 reducer 同样经过它
+pub type ReducerWrapper<S, A> = dyn Fn(Box<Reducer<S, A>>) -> Box<Reducer<S, A>> + 'static;
+
+/// 判断 state 是否变化：(旧 state, 新 state)
+pub type ChangeDetector<S> = dyn Fn(&S, &S) -> bool + 'static;
+
+// fallible reducer 出错后（已释放 inner 的 borrow）调用，把错误交给用户的 on_error
+type RejectHook<S, A> = dyn Fn(&Store<S, A>, &A) + 'static;
+
+// new_fallible 的 reducer 与 on_error 之间传递错误；replace_fallible_reducer 换上的 reducer 沿用
+struct RejectSlot<E> {
+    error: RefCell<Option<E>>,
+    // preview 被拒绝时不留下错误，否则之后真正的 dispatch 会读到它
+    previewing: PreviewFlag,
+}
+
+impl<E> RejectSlot<E> {
+    fn reduce<S, A>(
+        self: Rc<Self>,
+        reducer: impl Fn(&S, &A) -> Result<S, E> + 'static,
+    ) -> Box<ReduceFn<S, A>>
+    where
+        E: 'static,
+    {
+        Box::new(move |state: &S, action: &A| match reducer(state, action) {
+            Ok(next) => Some(next),
+            Err(error) => {
+                if !self.previewing.is_set() {
+                    *self.error.borrow_mut() = Some(error);
+                }
+                None
+            }
+        })
+    }
+}
+
+/// reducer 接受 action、state 提交之后调用（早于 listeners，不受 auto batch / change detection 影响）
+pub type CommitHook<S, A> = dyn Fn(&S, &A, Version) + 'static;
+
+/// restore 替换 state 之后调用（早于 listeners）
+pub type RestoreHook<S> = dyn Fn(&S, Version) + 'static;
+
 pub type Listener<S, A> = dyn FnMut(&S, &A) + 'static;
 
-#[derive(Clone)]
+/// 每次提交新 state（reducer 成功执行或 restore）加一；初始为 0
+/// 开启 change detection 时，state 未变化的提交不加
+pub type Version = u64;
+
+/// Store::preview 正在调用 reducer 时为 true：包装 reducer 的 enhancer（derive_slice、optimistic 等）
+/// 据此跳过 reducer 之外的记录（memo、日志），preview 才不会影响之后真正的 dispatch
+/// enhancer 自己创建，store 创建之后交给 Store::track_preview
+#[derive(Clone, Default)]
+pub struct PreviewFlag(Rc<Cell<bool>>);
+
+impl PreviewFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.0.get()
+    }
+}
+
+/// 标记一次 dispatch 的来源：dispatch_marked 的 action 进入 middleware 链时置为 true，
+/// middleware 用 take 读取并清除；同一个 store 上不同来源的 action 即使排队交错也不会认错
+/// （例如 sync_remote 的 replica 区分 primary 广播的 action 与本地 action）
+#[derive(Clone, Default)]
+pub struct DispatchMark(Rc<Cell<bool>>);
+
+impl DispatchMark {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 当前这一轮是否来自 dispatch_marked；读取后清除
+    pub fn take(&self) -> bool {
+        self.0.replace(false)
+    }
+}
+
+pub type VersionedListener<S, A> = dyn FnMut(&S, &A, Version) + 'static;
+
+type SharedListener<S, A> = Rc<RefCell<Box<VersionedListener<S, A>>>>;
+
+/// subscribe_to 的过滤条件：返回 false 的 action 不会调用 listener
+pub type ActionFilter<A> = dyn Fn(&A) -> bool + 'static;
+
+struct ListenerEntry<S, A> {
+    filter: Option<Rc<ActionFilter<A>>>,
+    // subscribe_weak 的 owner：已被 drop 的 listener 在通知时移除
+    owner: Option<Weak<dyn Any>>,
+    callback: SharedListener<S, A>,
+}
+
+// 手写 Clone：只克隆 Rc
+impl<S, A> Clone for ListenerEntry<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            filter: self.filter.clone(),
+            owner: self.owner.clone(),
+            callback: self.callback.clone(),
+        }
+    }
+}
+
+/// listener 在通知循环中 panic 时的处理策略
+/// no_std 下无法捕获 panic，所有策略都等同 Propagate
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListenerErrorPolicy {
+    /// 立即向 dispatch 的调用方传播，剩下的 listeners 本轮收不到通知
+    #[default]
+    Propagate,
+    /// 吞掉 panic，继续通知其余 listeners
+    ContinueOthers,
+    /// 同 ContinueOthers，并且把出错的 listener 退订
+    RemoveFaultyListener,
+}
+
+/// try_dispatch 的失败原因
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DispatchError {
+    /// 在 reducer 执行期间 dispatch
+    Reentrant,
+}
+
+impl fmt::Display for DispatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DispatchError::Reentrant => write!(f, "Reducers may not dispatch actions."),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+type PanicPayload = Box<dyn core::any::Any + Send>;
+#[cfg(not(feature = "std"))]
+type PanicPayload = core::convert::Infallible;
+
+// std 下捕获 panic，让调用方先复位内部状态再继续传播；no_std 下无法捕获（通常 panic = abort）
+#[cfg(feature = "std")]
+fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, PanicPayload> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+}
+
+#[cfg(not(feature = "std"))]
+fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, PanicPayload> {
+    Ok(f())
+}
+
+#[cfg(feature = "std")]
+fn resume_panic(payload: PanicPayload) -> ! {
+    panic::resume_unwind(payload)
+}
+
+#[cfg(not(feature = "std"))]
+fn resume_panic(payload: PanicPayload) -> ! {
+    match payload {}
+}
+
 pub struct Store<S, A> {
     inner: Rc<RefCell<Inner<S, A>>>,
+    queue: Rc<DispatchQueue<S, A>>,
+}
+
+// 手写 Clone：只克隆 Rc，不要求 S/A: Clone
+impl<S, A> Clone for Store<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+// 定时器回调持有的弱引用，避免 store -> timer -> 回调 -> store 的循环引用
+pub(crate) struct WeakStore<S, A> {
+    inner: Weak<RefCell<Inner<S, A>>>,
+    queue: Weak<DispatchQueue<S, A>>,
+}
+
+impl<S, A> WeakStore<S, A> {
+    pub(crate) fn upgrade(&self) -> Option<Store<S, A>> {
+        Some(Store {
+            inner: self.inner.upgrade()?,
+            queue: self.queue.upgrade()?,
+        })
+    }
+}
+
+// 等待当前这一轮结束后执行的工作（所有会提交 state / 通知 listeners 的入口）
+enum Job<S, A> {
+    // 嵌套 dispatch：从 middleware 链头开始；mark 见 dispatch_marked
+    Dispatch(A, Option<DispatchMark>),
+    // middleware 交给 next 的 action：只剩 reducer + 通知（包括保存下来稍后调用的 next）
+    Commit(A),
+    // flush_batched
+    Flush,
+    // restore：新 state 与 RESTORE action
+    Restore(S, A),
+}
+
+// 嵌套 dispatch 的 FIFO 队列；放在 inner 之外，reducer 执行期间（inner 被可变借用）也能访问
+struct DispatchQueue<S, A> {
+    // 有一轮 dispatch 正在进行（middleware + reducer + listeners）
+    dispatching: Cell<bool>,
+    // 正在提交 / 通知：此时到达的 Commit / Flush / Restore 也要排队，listener 看到的 (state, action) 才一致
+    committing: Cell<bool>,
+    // 防止 reducer 内部 dispatch（等价 Redux 的 isDispatching 约束）
+    reducing: Cell<bool>,
+    pending: RefCell<VecDeque<Job<S, A>>>,
+    // 见 track_preview
+    preview_flags: RefCell<Vec<PreviewFlag>>,
+}
+
+// 一轮 dispatch 结束（包括 panic 退出）时复位；panic 中断时丢弃排队的工作
+struct DispatchingGuard<'a, S, A>(&'a DispatchQueue<S, A>);
+
+impl<S, A> Drop for DispatchingGuard<'_, S, A> {
+    fn drop(&mut self) {
+        self.0.pending.borrow_mut().clear();
+        self.0.committing.set(false);
+        self.0.dispatching.set(false);
+    }
+}
+
+struct CommittingGuard<'a>(&'a Cell<bool>);
+
+impl Drop for CommittingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
 }
 
 struct Inner<S, A> {
-    reducer: Box<Reducer<S, A>>,
+    reducer: Box<ReduceFn<S, A>>,
+    on_rejected: Option<Rc<RejectHook<S, A>>>,
+    // new_fallible 的 RejectSlot<E>（见 replace_fallible_reducer）
+    reject_slot: Option<Rc<dyn Any>>,
+    // 见 on_replace_reducer
+    reducer_wrappers: Vec<Rc<ReducerWrapper<S, A>>>,
     state: S,
+    version: Version,
 
-    listeners: BTreeMap<ListenerId, Rc<RefCell<Box<Listener<S, A>>>>>,
+    listeners: BTreeMap<ListenerKey, ListenerEntry<S, A>>,
     next_listener_id: ListenerId,
 
-    // 防止 reducer 内部重入 dispatch（等价 Redux 的 isDispatching 约束）
-    is_reducing: bool,
+    // 按 apply 顺序排列，链头最先看到 action
+    middleware: Chain<S, A>,
+
+    listener_error_policy: ListenerErrorPolicy,
+
+    // 开发期的不可变性检查（release 构建下始终为 None）
+    immutable_check: Option<ImmutableCheck<S>>,
+
+    // 低优先级 action 的通知合并（见 enable_auto_batch）
+    auto_batch: Option<AutoBatch<A>>,
+
+    // 开启后 state 未变化的 dispatch 不通知（见 enable_change_detection）
+    change_detector: Option<Box<ChangeDetector<S>>>,
+
+    // 时间相关功能共用的定时器（见 set_timer）
+    timer: Option<Rc<dyn Timer>>,
+
+    // 见 on_commit
+    commit_hooks: Vec<Rc<CommitHook<S, A>>>,
+
+    // 见 on_restore
+    restore_hooks: Vec<Rc<RestoreHook<S>>>,
+
+    // 见 enable_metrics
+    metrics: Option<MetricsCollector<A>>,
+
+    // 按 action id 去重（见 enable_dedup）
+    dedup: Option<Dedup<A>>,
+
+    // subscribe_patches 共用的上一次 state 与 patch；由 patch listener 持有，
+    // 全部退订后失效，下一次订阅从当时的 state 重新开始
+    #[cfg(feature = "patch")]
+    patches: Weak<RefCell<PatchCache>>,
 }
 
 /// 订阅句柄：Drop 自动退订（你也可以手动 unsubscribe）
 pub struct Subscription {
     store: Weak<RefCell<dyn AnyUnsubscribe>>,
-    id: ListenerId,
+    key: ListenerKey,
     active: bool,
 }
 
 // 用 trait 做一次“类型擦除”，让 Subscription 不携带 S/A 泛型
-trait AnyUnsubscribe {
-    fn unsubscribe_by_id(&mut self, id: ListenerId);
+pub(crate) trait AnyUnsubscribe {
+    fn unsubscribe_by_key(&mut self, key: ListenerKey);
 }
 impl<S, A> AnyUnsubscribe for Inner<S, A> {
-    fn unsubscribe_by_id(&mut self, id: ListenerId) {
-        self.listeners.remove(&id);
+    fn unsubscribe_by_key(&mut self, key: ListenerKey) {
+        self.listeners.remove(&key);
     }
 }
 
 impl Subscription {
+    // 给其他实现 StoreApi 的 store（如 MockStore）复用同一个句柄类型
+    pub(crate) fn new(store: Weak<RefCell<dyn AnyUnsubscribe>>, key: ListenerKey) -> Self {
+        Self {
+            store,
+            key,
+            active: true,
+        }
+    }
+
+    // 放弃句柄但不退订（listener 的生命周期由别处管理，见 subscribe_weak）
+    pub(crate) fn detach(mut self) {
+        self.active = false;
+    }
+
+    /// 与 StoreMetrics::listeners 的 key 对应
+    pub fn id(&self) -> ListenerId {
+        self.key.1
+    }
+
     pub fn unsubscribe(mut self) {
         self.drop_impl();
         self.active = false;
@@ -54,7 +372,7 @@ impl Subscription {
         if let Some(rc) = self.store.upgrade() {
             // 这里用动态分发把 unsubscribe 调回具体 Inner
             let mut borrow = rc.borrow_mut();
-            borrow.unsubscribe_by_id(self.id);
+            borrow.unsubscribe_by_key(self.key);
         }
     }
 }
@@ -65,85 +383,948 @@ impl Drop for Subscription {
     }
 }
 
-impl<S, A> Store<S, A> {
+impl<S: 'static, A: 'static> Store<S, A> {
     /// createStore / Store::new：核心构造函数
     pub fn new(reducer: impl Fn(&S, &A) -> S + 'static, preloaded_state: S) -> Self {
+        Self::from_parts(
+            Box::new(move |state: &S, action: &A| Some(reducer(state, action))),
+            None,
+            preloaded_state,
+        )
+    }
+
+    /// 等价 Redux 的初始化约定：reducer 收到 Option<&S>，None 时返回自己的默认 state；
+    /// 构造时先用 init_action（通常是 InternalAction INIT）跑一次 reducer 得到初始 state
+    /// 此时还没有 middleware / listeners，之后的 action 都以 Some 调用 reducer
+    pub fn new_with_init(
+        reducer: impl Fn(Option<&S>, &A) -> S + 'static,
+        preloaded_state: Option<S>,
+        init_action: A,
+    ) -> Self {
+        let state = reducer(preloaded_state.as_ref(), &init_action);
+        Self::new(
+            move |state: &S, action: &A| reducer(Some(state), action),
+            state,
+        )
+    }
+
+    /// reducer 可以拒绝 action：返回 Err 时 state 不变、listeners 不会收到通知，
+    /// 错误交给 on_error（此时可以再 dispatch，例如一个表示失败的 error action）
+    pub fn new_fallible<E: 'static>(
+        reducer: impl Fn(&S, &A) -> Result<S, E> + 'static,
+        preloaded_state: S,
+        on_error: impl Fn(&Store<S, A>, E, &A) + 'static,
+    ) -> Self {
+        let rejected = Rc::new(RejectSlot {
+            error: RefCell::new(None),
+            previewing: PreviewFlag::new(),
+        });
+        let slot = rejected.clone();
+        let store = Self::from_parts(
+            rejected.clone().reduce(reducer),
+            Some(Rc::new(move |store: &Store<S, A>, action: &A| {
+                let error = slot.error.borrow_mut().take();
+                if let Some(error) = error {
+                    on_error(store, error, action);
+                }
+            })),
+            preloaded_state,
+        );
+        store.track_preview(&rejected.previewing);
+        store.inner.borrow_mut().reject_slot = Some(rejected);
+        store
+    }
+
+    fn from_parts(
+        reducer: Box<ReduceFn<S, A>>,
+        on_rejected: Option<Rc<RejectHook<S, A>>>,
+        preloaded_state: S,
+    ) -> Self {
         let inner = Inner {
-            reducer: Box::new(reducer),
+            reducer,
+            on_rejected,
+            reject_slot: None,
+            reducer_wrappers: Vec::new(),
             state: preloaded_state,
+            version: 0,
             listeners: BTreeMap::new(),
             next_listener_id: 0,
-            is_reducing: false,
+            middleware: Rc::new(Vec::new()),
+            listener_error_policy: ListenerErrorPolicy::default(),
+            immutable_check: None,
+            auto_batch: None,
+            change_detector: None,
+            timer: None,
+            commit_hooks: Vec::new(),
+            restore_hooks: Vec::new(),
+            metrics: None,
+            dedup: None,
+            #[cfg(feature = "patch")]
+            patches: Weak::new(),
         };
         Self {
             inner: Rc::new(RefCell::new(inner)),
+            queue: Rc::new(DispatchQueue {
+                dispatching: Cell::new(false),
+                committing: Cell::new(false),
+                reducing: Cell::new(false),
+                pending: RefCell::new(VecDeque::new()),
+                preview_flags: RefCell::new(Vec::new()),
+            }),
         }
     }
 
+    /// 是否是同一个 store（共享同一份 inner）
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
     /// Rust 风格：返回一个 state 的克隆快照
     ///（也可以提供 get_state_ref，但会让外部持有 borrow 更容易卡住 dispatch）
+    /// 除 reducer 内部外随时可用：listener、middleware（next 返回之后即为提交后的 state）、
+    /// on_commit hook、effects 里都能读取
     pub fn get_state(&self) -> S
     where
         S: Clone,
     {
+        self.assert_not_reducing("store.get_state()");
         self.inner.borrow().state.clone()
     }
 
-    /// 更接近 Redux：把 action 交给 reducer，更新 state，然后通知订阅者
-    pub fn dispatch(&self, action: A) {
+    // 在 borrow 内读取 state，避免克隆整个 state（f 里不能再 dispatch）
+    pub(crate) fn with_state<R>(&self, f: impl FnOnce(&S) -> R) -> R {
+        self.assert_not_reducing("store.get_state()");
+        f(&self.inner.borrow().state)
+    }
+
+    // reducer 执行期间 inner 处于可变借用中，给出明确的错误而不是 BorrowError
+    fn assert_not_reducing(&self, what: &str) {
+        if self.queue.reducing.get() {
+            panic!("You may not call {} while the reducer is executing.", what);
+        }
+    }
+
+    /// 等价 redux-thunk 的 dispatch(fn)：thunk 拿到 store，返回值原样交回调用方
+    /// （可以是 future / handle，调用方据此 await 结果，见 create_async_thunk）
+    /// thunk 本身不经过 middleware 链；middleware 也不能改写 dispatch 的返回值（Next 固定返回 ()），
+    /// 类型化的结果只能由 thunk 自己返回
+    pub fn dispatch_thunk<R>(&self, thunk: impl FnOnce(&Self) -> R) -> R {
+        thunk(self)
+    }
+
+    /// 同 dispatch，但在 reducer 内部调用时返回错误而不是 panic（no_std 下无法捕获 panic）
+    pub fn try_dispatch(&self, action: A) -> Result<(), DispatchError>
+    where
+        S: Clone,
+    {
+        if self.queue.reducing.get() {
+            return Err(DispatchError::Reentrant);
+        }
+        self.dispatch(action);
+        Ok(())
+    }
+
+    /// 更接近 Redux：action 先经过 middleware 链，再交给 reducer，更新 state，然后通知订阅者
+    /// 通知按 ListenerPriority 排序；本轮通知期间新增/退订的 listener 不影响这一轮
+    ///
+    /// 在 listener / middleware / effects 内部嵌套 dispatch 时，action 进入队列，
+    /// 当前这一轮结束后按 FIFO 依次处理，listener 看到的 (state, action) 总是一致的；
+    /// reducer 内部 dispatch 仍然 panic
+    pub fn dispatch(&self, action: A)
+    where
+        S: Clone,
+    {
+        if self.queue.reducing.get() {
+            panic!("Reducers may not dispatch actions (re-entrant dispatch detected).");
+        }
+        self.schedule(Job::Dispatch(action, None));
+    }
+
+    /// 同 dispatch；这个 action 进入 middleware 链时 mark 被置为 true（见 DispatchMark）
+    pub fn dispatch_marked(&self, action: A, mark: &DispatchMark)
+    where
+        S: Clone,
+    {
+        if self.queue.reducing.get() {
+            panic!("Reducers may not dispatch actions (re-entrant dispatch detected).");
+        }
+        self.schedule(Job::Dispatch(action, Some(mark.clone())));
+    }
+
+    // 空闲时开启一轮并按 FIFO 清空队列；一轮进行中时 dispatch 一律排队，
+    // 其余工作只在提交 / 通知期间排队（例如 middleware 同步调用 next 时直接执行）
+    fn schedule(&self, job: Job<S, A>)
+    where
+        S: Clone,
+    {
+        let busy = match job {
+            Job::Dispatch(..) => self.queue.dispatching.get(),
+            _ => self.queue.committing.get(),
+        };
+        if busy {
+            self.queue.pending.borrow_mut().push_back(job);
+            return;
+        }
+        if self.queue.dispatching.replace(true) {
+            return self.run_job(job);
+        }
+        let _guard = DispatchingGuard(&self.queue);
+        let mut next = Some(job);
+        while let Some(job) = next {
+            self.run_job(job);
+            let (depth, job) = {
+                let mut pending = self.queue.pending.borrow_mut();
+                (pending.len(), pending.pop_front())
+            };
+            if depth > 0
+                && let Some(metrics) = self.inner.borrow_mut().metrics.as_mut()
+            {
+                metrics.record_queue_depth(depth);
+            }
+            next = job;
+        }
+    }
+
+    fn run_job(&self, job: Job<S, A>)
+    where
+        S: Clone,
+    {
+        match job {
+            Job::Dispatch(action, mark) => self.run_pass(action, mark),
+            Job::Commit(action) => self.committing(|| self.reduce_and_notify(action)),
+            Job::Flush => self.committing(|| self.flush_now()),
+            Job::Restore(state, action) => self.committing(|| self.restore_now(state, action)),
+        }
+    }
+
+    // 提交 / 通知阶段；panic 被上游 middleware（例如 CrashReporter）捕获、这一轮继续时也要复位
+    fn committing(&self, f: impl FnOnce()) {
+        self.queue.committing.set(true);
+        let _guard = CommittingGuard(&self.queue.committing);
+        f();
+    }
+
+    /// what-if：只对当前 state 跑一次 reducer，返回假设的下一个 state
+    /// 不经过 middleware、不提交、不通知；reducer 拒绝（见 new_fallible）时返回当前 state
+    /// 包装 reducer 的 enhancer 通过 PreviewFlag 跳过自己的记录
+    pub fn preview(&self, action: &A) -> S
+    where
+        S: Clone,
+    {
+        if self.queue.reducing.get() {
+            panic!("Reducers may not preview actions.");
+        }
+        let inner = self.inner.borrow();
+        let flags = self.queue.preview_flags.borrow().clone();
+        let set_flags = |value| flags.iter().for_each(|flag| flag.0.set(value));
+        self.queue.reducing.set(true);
+        set_flags(true);
+        let result = catch_panic(|| (inner.reducer)(&inner.state, action));
+        set_flags(false);
+        self.queue.reducing.set(false);
+        result
+            .unwrap_or_else(|payload| resume_panic(payload))
+            .unwrap_or_else(|| inner.state.clone())
+    }
+
+    fn run_pass(&self, action: A, mark: Option<DispatchMark>)
+    where
+        S: Clone,
+    {
+        // 已经提交过的 id 在进入 middleware 之前就丢弃
+        if let Some(dedup) = self.inner.borrow_mut().dedup.as_mut()
+            && dedup.is_duplicate(&action)
+        {
+            return;
+        }
+        // snapshot middleware（本轮 dispatch 期间新增的 middleware 不影响这一轮）
+        let chain = self.inner.borrow().middleware.clone();
+        if chain.is_empty() {
+            return self.schedule(Job::Commit(action));
+        }
+        if let Some(mark) = &mark {
+            mark.0.set(true);
+        }
+        let store = self.clone();
+        run_chain(
+            Rc::new(self.clone()),
+            chain,
+            action,
+            Rc::new(move |action| store.schedule(Job::Commit(action))),
+        );
+        // 没有 middleware 读取时也不能留到下一轮
+        if let Some(mark) = mark {
+            mark.take();
+        }
+    }
+
+    fn reduce_and_notify(&self, action: A)
+    where
+        S: Clone,
+    {
         // 1) reducer 计算 next_state（只在这个阶段锁住 inner）
-        let (next_state, listeners_snapshot) = {
-            let mut inner = self.inner.borrow_mut();
+        let (changed, commit_hooks) = {
+            let mut guard = self.inner.borrow_mut();
+            let inner = &mut *guard;
 
-            if inner.is_reducing {
-                panic!("Reducers may not dispatch actions (re-entrant dispatch detected).");
+            if let Some(check) = &mut inner.immutable_check {
+                check.verify(&inner.state, "between dispatches");
+            }
+            // 同一个 id 可能在第一次提交之前就再次通过了 middleware（例如被 debounce 暂存）
+            if let Some(dedup) = inner.dedup.as_mut()
+                && dedup.is_duplicate(&action)
+            {
+                return;
             }
 
-            inner.is_reducing = true;
-            let next_state = (inner.reducer)(&inner.state, &action);
+            self.queue.reducing.set(true);
+            // reducer panic 时也要复位 reducing，否则上游捕获 panic 后 store 再也无法 dispatch
+            let started = inner.metrics.as_ref().map(MetricsCollector::now);
+            let result = catch_panic(|| (inner.reducer)(&inner.state, &action));
+            self.queue.reducing.set(false);
+            let next_state = result.unwrap_or_else(|payload| resume_panic(payload));
+            if let (Some(metrics), Some(started)) = (inner.metrics.as_mut(), started) {
+                let elapsed = metrics.now().saturating_sub(started);
+                metrics.record_reducer(&action, elapsed);
+            }
+            if let Some(check) = &mut inner.immutable_check {
+                check.verify(&inner.state, "inside a reducer");
+            }
+            let Some(next_state) = next_state else {
+                // 被拒绝：state 不变、不通知 listeners，错误交给 on_error
+                let hook = inner.on_rejected.clone();
+                drop(guard);
+                if let Some(hook) = hook {
+                    hook(self, &action);
+                }
+                return;
+            };
+            // 只有被 reducer 接受的 action 才占用 id
+            if let Some(dedup) = inner.dedup.as_mut() {
+                dedup.record(&action);
+            }
+            let changed = inner
+                .change_detector
+                .as_ref()
+                .is_none_or(|changed| changed(&inner.state, &next_state));
             inner.state = next_state;
-            inner.is_reducing = false;
+            if changed {
+                inner.version += 1;
+            }
 
-            // snapshot listeners（确保本轮 dispatch 稳定）
-            let snapshot: Vec<_> = inner.listeners.values().cloned().collect();
-            (inner.state_ref_clone_for_notify(), snapshot)
+            if let Some(check) = &mut inner.immutable_check {
+                check.record(&inner.state);
+            }
+            (changed, inner.commit_hooks.clone())
         };
+        if !commit_hooks.is_empty() {
+            // 不持有 borrow 调用，hook 里可以 subscribe / restore 等
+            let (state, version) = {
+                let inner = self.inner.borrow();
+                (inner.state.clone(), inner.version)
+            };
+            for hook in commit_hooks {
+                hook(&state, &action, version);
+            }
+        }
+        // 未变化：整个通知循环都跳过（version 也不变）
+        if !changed {
+            return;
+        }
 
-        // 2) 通知 listeners（此时不持有 inner 的 borrow）
-        for cb in listeners_snapshot {
-            cb.borrow_mut()(&next_state, &action);
+        // 2) 低优先级 action 只提交 state，通知推迟到 flush
+        let action = {
+            let mut inner = self.inner.borrow_mut();
+            match inner.auto_batch.as_mut() {
+                Some(batch) if batch.is_low_priority(&action) => {
+                    let schedule = batch.defer(action);
+                    let timer = inner.timer.clone();
+                    drop(inner);
+                    let weak = self.downgrade();
+                    let flush: Box<dyn FnOnce()> = Box::new(move || {
+                        if let Some(store) = weak.upgrade() {
+                            store.flush_batched();
+                        }
+                    });
+                    match schedule {
+                        Some(BatchSchedule::Callback(schedule)) => schedule(flush),
+                        Some(BatchSchedule::Timeout(delay)) => {
+                            // enable_auto_batch 已经检查过
+                            if let Some(timer) = timer {
+                                let id = timer.set_timeout(delay, flush);
+                                if let Some(batch) = self.inner.borrow_mut().auto_batch.as_mut() {
+                                    batch.arm(timer, id);
+                                }
+                            }
+                        }
+                        Some(BatchSchedule::Manual) | None => {}
+                    }
+                    return;
+                }
+                // 正常通知会带上最新 state，之前攒下的通知不再需要
+                Some(batch) => {
+                    batch.take();
+                    action
+                }
+                None => action,
+            }
+        };
+        self.notify(&action);
+    }
+
+    // 通知 listeners（此时不持有 inner 的 borrow）
+    fn notify(&self, action: &A)
+    where
+        S: Clone,
+    {
+        // snapshot listeners（确保本轮 dispatch 稳定）
+        let (state, version, listeners_snapshot, policy, clock) = {
+            let inner = self.inner.borrow();
+            let snapshot: Vec<_> = inner
+                .listeners
+                .iter()
+                .map(|(key, entry)| (*key, entry.clone()))
+                .collect();
+            (
+                inner.state_ref_clone_for_notify(),
+                inner.version,
+                snapshot,
+                inner.listener_error_policy,
+                inner.metrics.as_ref().map(MetricsCollector::clock),
+            )
+        };
+
+        for (key, entry) in listeners_snapshot {
+            if entry
+                .owner
+                .as_ref()
+                .is_some_and(|owner| owner.strong_count() == 0)
+            {
+                self.inner.borrow_mut().listeners.remove(&key);
+                continue;
+            }
+            // 过滤在 store 内完成，不匹配的 listener 完全不会被调用
+            if entry.filter.as_ref().is_some_and(|filter| !filter(action)) {
+                continue;
+            }
+            // restore / flush_batched 在通知期间排队（见 schedule），这里不会重入；保险起见跳过正在执行的 listener
+            let Ok(mut cb) = entry.callback.try_borrow_mut() else {
+                continue;
+            };
+            let started = clock.as_ref().map(|clock| clock.now());
+            if policy == ListenerErrorPolicy::Propagate {
+                cb(&state, action, version);
+            } else {
+                let result = catch_panic(|| cb(&state, action, version));
+                if result.is_err() && policy == ListenerErrorPolicy::RemoveFaultyListener {
+                    self.inner.borrow_mut().listeners.remove(&key);
+                    continue;
+                }
+            }
+            if let (Some(clock), Some(started)) = (&clock, started)
+                && let Some(metrics) = self.inner.borrow_mut().metrics.as_mut()
+            {
+                metrics.record_listener(key.1, clock.now().saturating_sub(started));
+            }
+        }
+    }
+
+    /// 发出 auto batch 攒下的通知（listeners 收到最新 state 和本批最后一个 action）
+    /// 在 listener 内部调用时排到当前这一轮之后
+    pub fn flush_batched(&self)
+    where
+        S: Clone,
+    {
+        self.schedule(Job::Flush);
+    }
+
+    fn flush_now(&self)
+    where
+        S: Clone,
+    {
+        let pending = self
+            .inner
+            .borrow_mut()
+            .auto_batch
+            .as_mut()
+            .and_then(|batch| batch.take());
+        if let Some(action) = pending {
+            self.notify(&action);
         }
     }
 
     /// 订阅：listener 接收 (&state, &action)
     /// 返回 Subscription：drop 自动退订
     pub fn subscribe(&self, listener: impl FnMut(&S, &A) + 'static) -> Subscription {
-        let (id, weak_any): (ListenerId, Weak<RefCell<dyn AnyUnsubscribe>>) = {
+        self.subscribe_with_priority(0, listener)
+    }
+
+    /// 带优先级的订阅，通知顺序见 ListenerPriority
+    pub fn subscribe_with_priority(
+        &self,
+        priority: ListenerPriority,
+        mut listener: impl FnMut(&S, &A) + 'static,
+    ) -> Subscription {
+        self.insert_listener(
+            priority,
+            None,
+            None,
+            Box::new(move |s, a, _| listener(s, a)),
+        )
+    }
+
+    /// listener 额外收到本次通知对应的 version，可用于廉价的“是否过期”判断
+    pub fn subscribe_versioned(
+        &self,
+        listener: impl FnMut(&S, &A, Version) + 'static,
+    ) -> Subscription {
+        self.insert_listener(0, None, None, Box::new(listener))
+    }
+
+    /// 只在 matcher(action) 为 true 时通知（按 type 前缀过滤见 matcher::type_prefix）
+    pub fn subscribe_to(
+        &self,
+        matcher: impl Fn(&A) -> bool + 'static,
+        mut listener: impl FnMut(&S, &A) + 'static,
+    ) -> Subscription {
+        self.insert_listener(
+            0,
+            Some(Rc::new(matcher)),
+            None,
+            Box::new(move |s, a, _| listener(s, a)),
+        )
+    }
+
+    /// listener 收到相对上一次通知的 JSON Patch，而不是整个 state
+    /// patch 只在有 patch listener 时计算，每次通知计算一次并在所有 patch listener 间共享；
+    /// 没有其他 patch listener 时，第一个 patch 相对订阅时的 state
+    /// state 无法序列化成 JSON 时 listener 收到 Err，之后的 patch 相对最后一次成功序列化的 state
+    #[cfg(feature = "patch")]
+    pub fn subscribe_patches(
+        &self,
+        listener: impl FnMut(Result<&[PatchOp], &serde_json::Error>, &A) + 'static,
+    ) -> Subscription
+    where
+        S: serde::Serialize,
+    {
+        let cache = {
+            let mut inner = self.inner.borrow_mut();
+            match inner.patches.upgrade() {
+                Some(cache) => cache,
+                None => {
+                    let cache = PatchCache::new(&inner.state, inner.version);
+                    inner.patches = Rc::downgrade(&cache);
+                    cache
+                }
+            }
+        };
+        self.subscribe_versioned(patch_listener(cache, listener))
+    }
+
+    /// listener 与 owner 同生命周期：内部只持有 Weak，owner 被 drop 后自动退订
+    /// （在下一次通知时移除），因此不返回 Subscription，也不用手动保存句柄
+    pub fn subscribe_weak<T: 'static>(
+        &self,
+        owner: &Rc<T>,
+        mut listener: impl FnMut(&T, &S, &A) + 'static,
+    ) {
+        let weak = Rc::downgrade(owner);
+        let erased: Weak<dyn Any> = weak.clone();
+        self.insert_listener(
+            0,
+            None,
+            Some(erased),
+            Box::new(move |s, a, _| {
+                if let Some(owner) = weak.upgrade() {
+                    listener(&owner, s, a);
+                }
+            }),
+        )
+        .detach();
+    }
+
+    fn insert_listener(
+        &self,
+        priority: ListenerPriority,
+        filter: Option<Rc<ActionFilter<A>>>,
+        owner: Option<Weak<dyn Any>>,
+        listener: Box<VersionedListener<S, A>>,
+    ) -> Subscription {
+        self.assert_not_reducing("store.subscribe()");
+        let (key, weak_any): (ListenerKey, Weak<RefCell<dyn AnyUnsubscribe>>) = {
             let mut inner = self.inner.borrow_mut();
-            let id = inner.next_listener_id;
+            let key = (priority, inner.next_listener_id);
             inner.next_listener_id += 1;
 
             inner.listeners.insert(
-                id,
-                Rc::new(RefCell::new(Box::new(listener) as Box<Listener<S, A>>)),
+                key,
+                ListenerEntry {
+                    filter,
+                    owner,
+                    callback: Rc::new(RefCell::new(listener)),
+                },
             );
 
             // 这里做一次类型擦除，让 Subscription 不带泛型
             let erased: Rc<RefCell<dyn AnyUnsubscribe>> = self.inner.clone();
-            (id, Rc::downgrade(&erased))
+            (key, Rc::downgrade(&erased))
         };
 
-        Subscription {
-            store: weak_any,
-            id,
-            active: true,
+        Subscription::new(weak_any, key)
+    }
+
+    /// 子 store：lens 投影出子 state，embed 把子 action 包回父 action，
+    /// extract 从父 action 中认出子 action；认不出的父 action 如何通知子 listener 见 ScopedStore
+    pub fn scope<Sub, SubA>(
+        &self,
+        lens: impl Fn(&S) -> Sub + 'static,
+        embed: impl Fn(SubA) -> A + 'static,
+        extract: impl Fn(&A) -> Option<SubA> + 'static,
+    ) -> ScopedStore<Sub, SubA>
+    where
+        S: Clone,
+        Sub: PartialEq + 'static,
+        SubA: From<InternalAction> + 'static,
+    {
+        ScopedStore::from_store(self, lens, embed, extract)
+    }
+
+    /// 持有一个派生值：每次通知后重新 select，不相等才更新并触发 on_change
+    pub fn watch<T: PartialEq + 'static>(
+        &self,
+        selector: impl Fn(&S) -> T + 'static,
+    ) -> Watched<T> {
+        Watched::from_store(self, selector)
+    }
+
+    /// 开发期检查：state 在 reducer 之外被修改（RefCell/Cell 等内部可变性）时 panic 或打印
+    /// 等价 RTK 的 immutable-state-invariant middleware；release 构建下为空操作
+    #[cfg(feature = "std")]
+    pub fn enable_immutable_check(&self, on_violation: OnViolation)
+    where
+        S: Debug,
+    {
+        self.enable_immutable_check_with(debug_fingerprint::<S>, on_violation);
+    }
+
+    /// 同上，但使用自定义指纹（例如 S: Hash 时直接哈希，避免格式化开销）
+    pub fn enable_immutable_check_with(
+        &self,
+        fingerprint: impl Fn(&S) -> u64 + 'static,
+        on_violation: OnViolation,
+    ) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        let mut inner = self.inner.borrow_mut();
+        let mut check = ImmutableCheck::new(Box::new(fingerprint), on_violation);
+        check.record(&inner.state);
+        inner.immutable_check = Some(check);
+    }
+
+    /// OnViolation::Log 的报告交给 report（默认打印到 stderr），例如接到日志框架
+    /// 在 enable_immutable_check 之后调用；没有开启检查时（包括 release 构建）为空操作
+    pub fn set_violation_report(&self, report: impl Fn(&str) + 'static) {
+        if let Some(check) = self.inner.borrow_mut().immutable_check.as_mut() {
+            check.set_report(Box::new(report));
+        }
+    }
+
+    /// 等价 RTK 的 autoBatchEnhancer：is_low_priority 为 true 的 action 立即更新 state，
+    /// listener 通知合并到 schedule 安排的下一次 flush（或手动 flush_batched）
+    /// BatchSchedule::Timeout 需要先 set_timer，否则在这里 panic
+    pub fn enable_auto_batch(
+        &self,
+        is_low_priority: impl Fn(&A) -> bool + 'static,
+        schedule: BatchSchedule,
+    ) {
+        if matches!(schedule, BatchSchedule::Timeout(_)) && self.timer().is_none() {
+            panic!(
+                "BatchSchedule::Timeout requires a timer. Call Store::set_timer before enabling auto batch."
+            );
+        }
+        self.inner.borrow_mut().auto_batch =
+            Some(AutoBatch::new(Box::new(is_low_priority), schedule));
+    }
+
+    /// state 未变化时跳过通知（例如未知 / 空操作的 action）
+    pub fn enable_change_detection(&self)
+    where
+        S: PartialEq,
+    {
+        self.enable_change_detection_with(|old: &S, new: &S| old != new);
+    }
+
+    /// 同上，由 changed(旧, 新) 判断是否变化（例如只比较版本字段或指针）
+    pub fn enable_change_detection_with(&self, changed: impl Fn(&S, &S) -> bool + 'static) {
+        self.inner.borrow_mut().change_detector = Some(Box::new(changed));
+    }
+
+    /// 幂等 dispatch：Action::id 在最近 capacity 个已提交的 id 中出现过的 action 直接丢弃
+    /// （不经过 middleware、reducer 和 listeners），用于重复投递的 webhook / 同步消息；
+    /// 被 middleware 吞掉或被 reducer 拒绝的 action 不记录 id，之后重新投递仍会处理
+    pub fn enable_dedup(&self, capacity: usize)
+    where
+        A: Action,
+    {
+        self.enable_dedup_with(capacity, |action: &A| action.id());
+    }
+
+    /// 同上，由 id 从 action 取唯一 id（A 不实现 Action 时）
+    pub fn enable_dedup_with(&self, capacity: usize, id: ActionId<A>) {
+        self.inner.borrow_mut().dedup = Some(Dedup::new(capacity, id));
+    }
+
+    /// 开启统计：每个 action type 的次数与 reducer 耗时、每个 listener 的耗时、嵌套 dispatch 队列深度
+    /// 耗时用 clock 测量（生产环境用 StdTimer 等，测试里 ManualTimer 的耗时总是 0）
+    pub fn enable_metrics(&self, clock: Rc<dyn Clock>)
+    where
+        A: Action,
+    {
+        self.enable_metrics_with(clock, |action: &A| action.type_());
+    }
+
+    /// 同上，由 label 从 action 取统计用的 type（A 不实现 Action 时）
+    pub fn enable_metrics_with(&self, clock: Rc<dyn Clock>, label: ActionLabel<A>) {
+        self.inner.borrow_mut().metrics = Some(MetricsCollector::new(clock, label));
+    }
+
+    /// 到目前为止的统计；未开启时为 None
+    pub fn metrics(&self) -> Option<StoreMetrics> {
+        self.inner
+            .borrow()
+            .metrics
+            .as_ref()
+            .map(MetricsCollector::snapshot)
+    }
+
+    /// 清空已收集的统计（保持开启）
+    pub fn reset_metrics(&self) {
+        if let Some(metrics) = self.inner.borrow_mut().metrics.as_mut() {
+            metrics.reset();
+        }
+    }
+
+    /// preview 调用 reducer 期间把 flag 置为 true（见 PreviewFlag）
+    pub fn track_preview(&self, flag: &PreviewFlag) {
+        self.queue.preview_flags.borrow_mut().push(flag.clone());
+    }
+
+    /// 每个被 reducer 接受的 action 都会调用 hook（state 未变化、通知被合并时也一样），
+    /// 适合审计日志 / journal
+    pub fn on_commit(&self, hook: impl Fn(&S, &A, Version) + 'static) {
+        self.inner.borrow_mut().commit_hooks.push(Rc::new(hook));
+    }
+
+    /// restore 绕过 reducer 替换 state 时调用；只靠 on_commit 重放 action 的记录（journal 等）
+    /// 需要在这里记下新的 state
+    pub fn on_restore(&self, hook: impl Fn(&S, Version) + 'static) {
+        self.inner.borrow_mut().restore_hooks.push(Rc::new(hook));
+    }
+
+    /// 设置 listener panic 时的处理策略（默认 Propagate）
+    pub fn set_listener_error_policy(&self, policy: ListenerErrorPolicy) {
+        self.inner.borrow_mut().listener_error_policy = policy;
+    }
+
+    /// 追加 middleware（类似 applyMiddleware）；先 apply 的在链上更靠前
+    pub fn apply_middleware(&self, middleware: impl Middleware<S, A> + 'static) {
+        Rc::make_mut(&mut self.inner.borrow_mut().middleware).push(Rc::new(middleware));
+    }
+
+    // 放到链头（保持这一批内部的顺序）：外层 enhancer 装的 middleware 先看到 action
+    pub(crate) fn prepend_middleware(&self, middleware: Vec<Rc<dyn Middleware<S, A>>>) {
+        Rc::make_mut(&mut self.inner.borrow_mut().middleware).splice(0..0, middleware);
+    }
+
+    /// 导出当前 state（连同 version）
+    pub fn snapshot(&self) -> StateSnapshot<S>
+    where
+        S: Clone,
+    {
+        self.assert_not_reducing("store.snapshot()");
+        let inner = self.inner.borrow();
+        StateSnapshot {
+            state: inner.state.clone(),
+            version: inner.version,
+        }
+    }
+
+    /// 强制替换 state：不经过 middleware / reducer，
+    /// listeners 恰好收到一次 @@redux/RESTORE（auto batch 攒下的通知被丢弃）
+    /// 在 listener / commit hook 内部调用时排到当前这一轮之后，其余 listener 先看完本轮通知
+    pub fn restore(&self, snapshot: StateSnapshot<S>)
+    where
+        S: Clone,
+        A: From<InternalAction>,
+    {
+        if self.queue.reducing.get() {
+            panic!("Reducers may not restore state.");
+        }
+        self.schedule(Job::Restore(
+            snapshot.into_state(),
+            A::from(InternalAction {
+                kind: InternalActionType::Restore,
+            }),
+        ));
+    }
+
+    fn restore_now(&self, state: S, action: A)
+    where
+        S: Clone,
+    {
+        let restore_hooks = {
+            let mut inner = self.inner.borrow_mut();
+            inner.state = state;
+            // restore 也是一次提交；snapshot 里的 version 只作记录，序号保持单调
+            inner.version += 1;
+            let inner = &mut *inner;
+            if let Some(check) = &mut inner.immutable_check {
+                check.record(&inner.state);
+            }
+            if let Some(batch) = &mut inner.auto_batch {
+                batch.take();
+            }
+            inner.restore_hooks.clone()
+        };
+        if !restore_hooks.is_empty() {
+            let (state, version) = {
+                let inner = self.inner.borrow();
+                (inner.state.clone(), inner.version)
+            };
+            for hook in restore_hooks {
+                hook(&state, version);
+            }
         }
+        self.notify(&action);
+    }
+
+    /// 当前 state 的序号（见 Version）
+    pub fn version(&self) -> Version {
+        self.inner.borrow().version
+    }
+
+    /// 排队等待当前这一轮结束后处理的工作数（嵌套的 dispatch / restore / flush_batched）
+    pub fn queued_dispatches(&self) -> usize {
+        self.queue.pending.borrow().len()
     }
 
-    /// 可选：替换 reducer（类似 replaceReducer）
+    /// 注入定时器：BatchSchedule::Timeout 等时间相关功能都从这里取时间，
+    /// 测试里换成 ManualTimer 即可完全确定
+    pub fn set_timer(&self, timer: Rc<dyn Timer>) {
+        self.inner.borrow_mut().timer = Some(timer);
+    }
+
+    pub fn timer(&self) -> Option<Rc<dyn Timer>> {
+        self.inner.borrow().timer.clone()
+    }
+
+    pub(crate) fn downgrade(&self) -> WeakStore<S, A> {
+        WeakStore {
+            inner: Rc::downgrade(&self.inner),
+            queue: Rc::downgrade(&self.queue),
+        }
+    }
+
+    /// delay 之后 dispatch action（使用 set_timer 注入的定时器）；到时 store 已被 drop 则忽略
+    pub fn dispatch_after(&self, delay: Duration, action: A) -> ScheduleHandle
+    where
+        S: Clone,
+    {
+        let timer = self
+            .timer()
+            .expect("Store::dispatch_after requires a timer. Call Store::set_timer first.");
+        let weak = self.downgrade();
+        schedule::after(
+            timer,
+            delay,
+            Box::new(move || {
+                if let Some(store) = weak.upgrade() {
+                    store.dispatch(action);
+                }
+            }),
+        )
+    }
+
+    /// 每隔 interval dispatch 一次 action 的克隆，直到 cancel 或 store 被 drop
+    /// 默认不受 restore 影响；需要按恢复出来的 state 重新安排时见 ScheduleHandle::reschedule_on_restore
+    pub fn dispatch_every(&self, interval: Duration, action: A) -> ScheduleHandle
+    where
+        S: Clone,
+        A: Clone,
+    {
+        let timer = self
+            .timer()
+            .expect("Store::dispatch_every requires a timer. Call Store::set_timer first.");
+        let weak = self.downgrade();
+        schedule::every(
+            timer,
+            interval,
+            Rc::new(move || match weak.upgrade() {
+                Some(store) => {
+                    store.dispatch(action.clone());
+                    true
+                }
+                None => false,
+            }),
+        )
+    }
+
+    /// replace_reducer 换上的新 reducer 先交给 wrap 包装（等价 Redux enhancer 包装 replaceReducer）；
+    /// 包装 reducer 的 enhancer（derive_slice、optimistic、diagnostics）在 store 创建之后注册。
+    /// 后注册的先包装：enhancer 由内向外注册，新 reducer 的包装顺序与创建时一致
+    pub fn on_replace_reducer(
+        &self,
+        wrap: impl Fn(Box<Reducer<S, A>>) -> Box<Reducer<S, A>> + 'static,
+    ) {
+        self.inner.borrow_mut().reducer_wrappers.push(Rc::new(wrap));
+    }
+
+    /// 可选：替换 reducer（类似 replaceReducer），经过 on_replace_reducer 注册的包装；
+    /// new_fallible 创建的 store 换上的 reducer 不会再拒绝 action（见 replace_fallible_reducer）
     pub fn replace_reducer(&self, next: impl Fn(&S, &A) -> S + 'static) {
-        let mut inner = self.inner.borrow_mut();
-        inner.reducer = Box::new(next);
+        self.assert_not_reducing("store.replace_reducer()");
+        let wrappers = self.inner.borrow().reducer_wrappers.clone();
+        let next = wrappers
+            .iter()
+            .rev()
+            .fold(Box::new(next) as Box<Reducer<S, A>>, |reducer, wrap| {
+                wrap(reducer)
+            });
+        self.inner.borrow_mut().reducer =
+            Box::new(move |state: &S, action: &A| Some(next(state, action)));
+    }
+
+    /// 替换 new_fallible 创建的 store 的 reducer，错误仍交给创建时的 on_error
+    /// E 必须与 new_fallible 的错误类型相同
+    pub fn replace_fallible_reducer<E: 'static>(
+        &self,
+        next: impl Fn(&S, &A) -> Result<S, E> + 'static,
+    ) {
+        self.assert_not_reducing("store.replace_fallible_reducer()");
+        let slot = self
+            .inner
+            .borrow()
+            .reject_slot
+            .clone()
+            .and_then(|slot| slot.downcast::<RejectSlot<E>>().ok())
+            .expect(
+                "Store::replace_fallible_reducer requires a store created by Store::new_fallible with the same error type.",
+            );
+        self.inner.borrow_mut().reducer = slot.reduce(next);
+    }
+
+    /// 替换 reducer 后 dispatch replace_action（通常是 InternalAction REPLACE），
+    /// 让新 reducer 有机会补上自己的 state（等价 Redux replaceReducer 的 REPLACE）
+    pub fn replace_reducer_with_action(
+        &self,
+        next: impl Fn(&S, &A) -> S + 'static,
+        replace_action: A,
+    ) where
+        S: Clone,
+    {
+        self.replace_reducer(next);
+        self.dispatch(replace_action);
     }
 }
 
@@ -156,3 +1337,608 @@ impl<S, A> Inner<S, A> {
         self.state.clone()
     }
 }
+
+impl<S: Clone + 'static, A: 'static> MiddlewareApi<S, A> for Store<S, A> {
+    fn get_state(&self) -> S {
+        Store::get_state(self)
+    }
+
+    fn dispatch(&self, action: A) {
+        Store::dispatch(self, action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    #[cfg(feature = "std")]
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    use super::*;
+    use crate::core::middleware::Next;
+    use crate::core::storet::AppAction;
+    use crate::core::timer::ManualTimer;
+
+    fn counter() -> Store<i32, i32> {
+        Store::new(|state: &i32, action: &i32| state + action, 0)
+    }
+
+    #[test]
+    fn listener_reads_committed_state() {
+        let store = counter();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (reader, log) = (store.clone(), seen.clone());
+        let _sub = store.subscribe(move |state, _| {
+            log.borrow_mut().push((*state, reader.get_state()));
+        });
+        store.dispatch(1);
+        store.dispatch(2);
+        assert_eq!(*seen.borrow(), vec![(1, 1), (3, 3)]);
+    }
+
+    #[test]
+    fn middleware_reads_state_after_next() {
+        let store = counter();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        store.apply_middleware(
+            move |api: &dyn MiddlewareApi<i32, i32>, action: i32, next: Next<i32>| {
+                let before = api.get_state();
+                next(action);
+                log.borrow_mut().push((before, api.get_state()));
+            },
+        );
+        store.dispatch(5);
+        assert_eq!(*seen.borrow(), vec![(0, 5)]);
+    }
+
+    #[test]
+    fn nested_dispatch_from_listener_is_queued_in_order() {
+        let store = counter();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (inner, log) = (store.clone(), seen.clone());
+        let _first = store.subscribe(move |state, action| {
+            log.borrow_mut().push(("first", *state, *action));
+            if *action == 1 {
+                inner.dispatch(10);
+                inner.dispatch(100);
+            }
+        });
+        let log = seen.clone();
+        let _second = store.subscribe(move |state, action| {
+            log.borrow_mut().push(("second", *state, *action));
+        });
+        store.dispatch(1);
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                ("first", 1, 1),
+                ("second", 1, 1),
+                ("first", 11, 10),
+                ("second", 11, 10),
+                ("first", 111, 100),
+                ("second", 111, 100),
+            ]
+        );
+    }
+
+    type Seen<T = (i32, i32)> = Rc<RefCell<Vec<T>>>;
+
+    // 第一个 listener 在看到 trigger 时 dispatch 100；返回第二个 listener 看到的 (state, action)
+    fn dispatch_on(store: &Store<i32, i32>, trigger: i32) -> (Seen, [Subscription; 2]) {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let inner = store.clone();
+        let first = store.subscribe(move |_, action| {
+            if *action == trigger {
+                inner.dispatch(100);
+            }
+        });
+        let log = seen.clone();
+        let second = store.subscribe(move |state, action| log.borrow_mut().push((*state, *action)));
+        (seen, [first, second])
+    }
+
+    #[test]
+    fn batched_flush_from_timer_queues_nested_dispatch() {
+        let store = counter();
+        let timer = Rc::new(ManualTimer::new());
+        store.set_timer(timer.clone());
+        store.enable_auto_batch(
+            |action: &i32| *action == 1,
+            BatchSchedule::Timeout(Duration::from_millis(10)),
+        );
+        let (seen, _subs) = dispatch_on(&store, 1);
+        store.dispatch(1);
+        assert!(seen.borrow().is_empty());
+        timer.advance(Duration::from_millis(10));
+        assert_eq!(*seen.borrow(), vec![(1, 1), (101, 100)]);
+        assert_eq!(store.get_state(), 101);
+    }
+
+    #[test]
+    fn taken_batch_clears_its_flush_timer() {
+        let store = counter();
+        let timer = Rc::new(ManualTimer::new());
+        store.set_timer(timer.clone());
+        store.enable_auto_batch(
+            |action: &i32| *action == 1,
+            BatchSchedule::Timeout(Duration::from_millis(10)),
+        );
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let _sub = store.subscribe(move |state, action| log.borrow_mut().push((*state, *action)));
+        // low → normal：正常通知带走这一批，定时器一并取消
+        store.dispatch(1);
+        timer.advance(Duration::from_millis(5));
+        store.dispatch(2);
+        assert_eq!(timer.pending(), 0);
+        // 新的一批按自己的 Timeout 计时，不会被旧定时器提前 flush
+        store.dispatch(1);
+        timer.advance(Duration::from_millis(5));
+        assert_eq!(*seen.borrow(), vec![(3, 2)]);
+        timer.advance(Duration::from_millis(5));
+        assert_eq!(*seen.borrow(), vec![(3, 2), (4, 1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "BatchSchedule::Timeout requires a timer.")]
+    fn timeout_batch_requires_timer_up_front() {
+        counter().enable_auto_batch(
+            |_: &i32| true,
+            BatchSchedule::Timeout(Duration::from_millis(10)),
+        );
+    }
+
+    #[test]
+    fn deferred_next_from_timer_queues_nested_dispatch() {
+        let store = counter();
+        let timer = Rc::new(ManualTimer::new());
+        let later = timer.clone();
+        store.apply_middleware(
+            move |_: &dyn MiddlewareApi<i32, i32>, action: i32, next: Next<i32>| {
+                if action == 1 {
+                    later.set_timeout(Duration::from_millis(10), Box::new(move || next(action)));
+                } else {
+                    next(action);
+                }
+            },
+        );
+        let (seen, _subs) = dispatch_on(&store, 1);
+        store.dispatch(1);
+        timer.advance(Duration::from_millis(10));
+        assert_eq!(*seen.borrow(), vec![(1, 1), (101, 100)]);
+        assert_eq!(store.get_state(), 101);
+    }
+
+    #[test]
+    fn restore_outside_dispatch_queues_nested_dispatch() {
+        let store = app_counter(5);
+        let inner = store.clone();
+        let _first = store.subscribe(move |_, action| {
+            if let AppAction::Internal(_) = action {
+                inner.dispatch(AppAction::Business(100));
+            }
+        });
+        let (seen, _second) = record_app(&store);
+        store.restore(StateSnapshot::new(0));
+        assert_eq!(*seen.borrow(), vec![(0, true), (100, false)]);
+        assert_eq!(store.get_state(), 100);
+    }
+
+    #[test]
+    fn listener_can_subscribe_and_unsubscribe_during_notify() {
+        let store = counter();
+        let added = Rc::new(RefCell::new(Vec::new()));
+        let calls = Rc::new(Cell::new(0));
+        let (inner, slot, count) = (store.clone(), added.clone(), calls.clone());
+        let sub = store.subscribe(move |_, _| {
+            let count = count.clone();
+            slot.borrow_mut()
+                .push(inner.subscribe(move |_, _| count.set(count.get() + 1)));
+        });
+        store.dispatch(1);
+        // 本轮新增的 listener 不会收到这一次通知
+        assert_eq!(calls.get(), 0);
+        sub.unsubscribe();
+        store.dispatch(1);
+        assert_eq!(calls.get(), 1);
+        added.borrow_mut().clear();
+        store.dispatch(1);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn priority_orders_notification_then_subscription_order() {
+        let store = counter();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut subs = Vec::new();
+        for (name, priority) in [
+            ("ui", 0),
+            ("late", 5),
+            ("persist", -1),
+            ("ui2", 0),
+            ("devtools", -1),
+        ] {
+            let log = seen.clone();
+            subs.push(
+                store.subscribe_with_priority(priority, move |_, _| log.borrow_mut().push(name)),
+            );
+        }
+        store.dispatch(1);
+        assert_eq!(
+            *seen.borrow(),
+            vec!["persist", "devtools", "ui", "ui2", "late"]
+        );
+    }
+
+    #[test]
+    fn unsubscribe_during_notify_applies_from_next_round() {
+        let store = counter();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let later: Rc<RefCell<Option<Subscription>>> = Rc::new(RefCell::new(None));
+        let slot = later.clone();
+        let _first = store.subscribe_with_priority(-1, move |_, _| {
+            if let Some(sub) = slot.borrow_mut().take() {
+                sub.unsubscribe();
+            }
+        });
+        let log = seen.clone();
+        *later.borrow_mut() =
+            Some(store.subscribe_with_priority(1, move |_, action| log.borrow_mut().push(*action)));
+        store.dispatch(1);
+        store.dispatch(2);
+        assert_eq!(*seen.borrow(), vec![1]);
+    }
+
+    #[test]
+    fn change_detection_skips_listeners_for_equal_state() {
+        let store = counter();
+        store.enable_change_detection();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let _sub = store.subscribe(move |state, action| log.borrow_mut().push((*state, *action)));
+        store.dispatch(1);
+        store.dispatch(0);
+        store.dispatch(2);
+        assert_eq!(*seen.borrow(), vec![(1, 1), (3, 2)]);
+        assert_eq!(store.version(), 2);
+    }
+
+    #[test]
+    fn change_detection_with_custom_comparison() {
+        let store = counter();
+        // 只关心奇偶性的变化
+        store.enable_change_detection_with(|old: &i32, new: &i32| old % 2 != new % 2);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let _sub = store.subscribe(move |state, action| log.borrow_mut().push((*state, *action)));
+        store.dispatch(2);
+        store.dispatch(1);
+        store.dispatch(2);
+        assert_eq!(*seen.borrow(), vec![(3, 1)]);
+        // 被跳过的提交照样生效
+        assert_eq!(store.get_state(), 5);
+    }
+
+    #[test]
+    fn weak_listener_is_pruned_after_owner_drops() {
+        let store = counter();
+        let owner = Rc::new(RefCell::new(Vec::new()));
+        let calls = Rc::new(Cell::new(0));
+        let count = calls.clone();
+        store.subscribe_weak(&owner, move |seen, state, _| {
+            count.set(count.get() + 1);
+            seen.borrow_mut().push(*state);
+        });
+        store.dispatch(1);
+        assert_eq!(*owner.borrow(), vec![1]);
+        assert_eq!(store.inner.borrow().listeners.len(), 1);
+
+        // listener 不会让 owner 继续存活
+        let weak = Rc::downgrade(&owner);
+        drop(owner);
+        assert!(weak.upgrade().is_none());
+        store.dispatch(1);
+        assert_eq!(calls.get(), 1);
+        assert!(store.inner.borrow().listeners.is_empty());
+    }
+
+    #[test]
+    fn commit_hook_can_read_state_and_subscribe() {
+        let store = counter();
+        let subs = Rc::new(RefCell::new(Vec::new()));
+        let (inner, slot) = (store.clone(), subs.clone());
+        store.on_commit(move |state, _, _| {
+            assert_eq!(*state, inner.get_state());
+            slot.borrow_mut().push(inner.subscribe(|_, _| {}));
+        });
+        store.dispatch(1);
+        store.dispatch(2);
+        assert_eq!(subs.borrow().len(), 2);
+    }
+
+    fn app_counter(preloaded_state: i32) -> Store<i32, AppAction<i32>> {
+        Store::new(
+            |state: &i32, action: &AppAction<i32>| match action {
+                AppAction::Business(n) => state + n,
+                AppAction::Internal(_) => *state,
+            },
+            preloaded_state,
+        )
+    }
+
+    fn label(action: &AppAction<i32>) -> &'static str {
+        match action {
+            AppAction::Business(_) => "business",
+            AppAction::Internal(InternalAction { kind }) => match kind {
+                InternalActionType::Init => "init",
+                InternalActionType::Replace => "replace",
+                InternalActionType::Restore => "restore",
+            },
+        }
+    }
+
+    fn internal(kind: InternalActionType) -> AppAction<i32> {
+        AppAction::Internal(InternalAction { kind })
+    }
+
+    // 记录 reducer 收到的 (state 是否存在, action)
+    fn init_aware(
+        log: Seen<(bool, &'static str)>,
+    ) -> impl Fn(Option<&i32>, &AppAction<i32>) -> i32 {
+        move |state, action| {
+            log.borrow_mut().push((state.is_some(), label(action)));
+            match (state, action) {
+                (None, _) => 10,
+                (Some(state), AppAction::Business(n)) => state + n,
+                (Some(state), AppAction::Internal(_)) => *state,
+            }
+        }
+    }
+
+    #[test]
+    fn init_action_builds_initial_state() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let init = || internal(InternalActionType::Init);
+        let store = Store::new_with_init(init_aware(calls.clone()), None, init());
+        assert_eq!(store.get_state(), 10);
+        let (seen, _sub) = record_app(&store);
+        store.dispatch(AppAction::Business(1));
+        assert_eq!(store.get_state(), 11);
+        assert_eq!(*calls.borrow(), vec![(false, "init"), (true, "business")]);
+        // INIT 在构造时处理，之后订阅的 listener 只看到后续 action
+        assert_eq!(*seen.borrow(), vec![(11, false)]);
+
+        // 有 preloaded state 时 reducer 以 Some 收到 INIT
+        calls.borrow_mut().clear();
+        let store = Store::new_with_init(init_aware(calls.clone()), Some(3), init());
+        assert_eq!(store.get_state(), 3);
+        assert_eq!(*calls.borrow(), vec![(true, "init")]);
+    }
+
+    #[test]
+    fn replace_action_reaches_new_reducer_and_listeners() {
+        let store = app_counter(1);
+        let (seen, _sub) = record_app(&store);
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let log = calls.clone();
+        store.replace_reducer_with_action(
+            move |state: &i32, action: &AppAction<i32>| {
+                log.borrow_mut().push(label(action));
+                match action {
+                    AppAction::Business(n) => state * n,
+                    AppAction::Internal(_) => state + 100,
+                }
+            },
+            internal(InternalActionType::Replace),
+        );
+        assert_eq!(*calls.borrow(), vec!["replace"]);
+        assert_eq!(*seen.borrow(), vec![(101, true)]);
+        store.dispatch(AppAction::Business(2));
+        assert_eq!(store.get_state(), 202);
+    }
+
+    // (state, 是否 RESTORE)
+    fn record_app(store: &Store<i32, AppAction<i32>>) -> (Seen<(i32, bool)>, Subscription) {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let sub = store.subscribe(move |state, action| {
+            log.borrow_mut()
+                .push((*state, matches!(action, AppAction::Internal(_))));
+        });
+        (seen, sub)
+    }
+
+    #[test]
+    fn version_counts_commits_and_restores_but_not_previews() {
+        let store = app_counter(0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let _sub = store.subscribe_versioned(move |state, _, version| {
+            log.borrow_mut().push((*state, version));
+        });
+        assert_eq!(store.version(), 0);
+        store.dispatch(AppAction::Business(1));
+        store.dispatch(AppAction::Business(2));
+        assert_eq!(store.version(), 2);
+        assert_eq!(store.preview(&AppAction::Business(10)), 13);
+        assert_eq!(store.version(), 2);
+        store.restore(StateSnapshot::new(0));
+        assert_eq!(store.version(), 3);
+        assert_eq!(*seen.borrow(), vec![(1, 1), (3, 2), (0, 3)]);
+    }
+
+    #[test]
+    fn restore_from_listener_runs_after_current_notification() {
+        let store = app_counter(0);
+        let calls = Rc::new(Cell::new(0));
+        let (inner, count) = (store.clone(), calls.clone());
+        let _first = store.subscribe(move |state, _| {
+            count.set(count.get() + 1);
+            if *state > 10 {
+                inner.restore(StateSnapshot::new(0));
+            }
+        });
+        let (seen, _second) = record_app(&store);
+        store.dispatch(AppAction::Business(20));
+        assert_eq!(store.get_state(), 0);
+        // 恢复的 listener 自己也只在本轮结束后再收到一次 RESTORE
+        assert_eq!(calls.get(), 2);
+        assert_eq!(*seen.borrow(), vec![(20, false), (0, true)]);
+    }
+
+    #[test]
+    fn restore_from_commit_hook_runs_after_notification() {
+        let store = app_counter(0);
+        let inner = store.clone();
+        store.on_commit(move |state, _, _| {
+            if *state > 10 {
+                inner.restore(StateSnapshot::new(0));
+            }
+        });
+        let (seen, _sub) = record_app(&store);
+        store.dispatch(AppAction::Business(20));
+        assert_eq!(store.get_state(), 0);
+        assert_eq!(*seen.borrow(), vec![(20, false), (0, true)]);
+    }
+
+    #[test]
+    fn rejected_preview_leaves_no_error_behind() {
+        let token = Rc::new(());
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let (error, log) = (token.clone(), errors.clone());
+        let store = Store::new_fallible(
+            move |state: &i32, action: &i32| {
+                if *action < 0 {
+                    Err((error.clone(), *action))
+                } else {
+                    Ok(state + action)
+                }
+            },
+            0,
+            move |_, (_, rejected): (Rc<()>, i32), action: &i32| {
+                log.borrow_mut().push((rejected, *action));
+            },
+        );
+        assert_eq!(store.preview(&-1), 0);
+        // 被拒绝的 preview 产生的错误已经丢弃
+        assert_eq!(Rc::strong_count(&token), 2);
+        store.dispatch(1);
+        store.dispatch(-2);
+        assert_eq!(*errors.borrow(), vec![(-2, -2)]);
+        assert_eq!(Rc::strong_count(&token), 2);
+    }
+
+    #[test]
+    fn replaced_fallible_reducer_reports_to_on_error() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let log = errors.clone();
+        let store = Store::new_fallible(
+            |state: &i32, action: &i32| {
+                if *action < 0 {
+                    Err(*action)
+                } else {
+                    Ok(state + action)
+                }
+            },
+            0,
+            move |_, error: i32, _: &i32| log.borrow_mut().push(error),
+        );
+        // 新 reducer 拒绝大于 10 的 action
+        store.replace_fallible_reducer(|state: &i32, action: &i32| {
+            if *action > 10 {
+                Err(*action)
+            } else {
+                Ok(state + action)
+            }
+        });
+        store.dispatch(-1);
+        store.dispatch(11);
+        assert_eq!(store.get_state(), -1);
+        assert_eq!(*errors.borrow(), vec![11]);
+    }
+
+    #[test]
+    #[should_panic(expected = "You may not call store.get_state() while the reducer is executing.")]
+    fn get_state_inside_reducer_panics() {
+        let slot: Rc<RefCell<Option<Store<i32, i32>>>> = Rc::new(RefCell::new(None));
+        let reader = slot.clone();
+        let store = Store::new(
+            move |state: &i32, _: &i32| {
+                let store = reader.borrow().clone().unwrap();
+                state + store.get_state()
+            },
+            0,
+        );
+        *slot.borrow_mut() = Some(store.clone());
+        store.dispatch(1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn store_is_usable_after_reducer_panic() {
+        let store = Store::new(
+            |state: &i32, action: &i32| {
+                assert!(*action >= 0, "negative");
+                state + action
+            },
+            1,
+        );
+        let result = catch_unwind(AssertUnwindSafe(|| store.dispatch(-1)));
+        assert!(result.is_err());
+        assert_eq!(store.get_state(), 1);
+        store.dispatch(2);
+        assert_eq!(store.get_state(), 3);
+    }
+
+    // 中间的 listener 在 action 为 1 时 panic；记录前后两个 listener 看到的 action
+    #[cfg(feature = "std")]
+    fn faulty_listener(policy: ListenerErrorPolicy) -> (Store<i32, i32>, Seen, [Subscription; 3]) {
+        let store = counter();
+        store.set_listener_error_policy(policy);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let before = store.subscribe(move |_, action| log.borrow_mut().push((0, *action)));
+        let log = seen.clone();
+        let faulty = store.subscribe(move |_, action| {
+            log.borrow_mut().push((1, *action));
+            assert!(*action != 1, "faulty listener");
+        });
+        let log = seen.clone();
+        let after = store.subscribe(move |_, action| log.borrow_mut().push((2, *action)));
+        (store, seen, [before, faulty, after])
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn propagate_policy_rethrows_and_skips_remaining_listeners() {
+        let (store, seen, _subs) = faulty_listener(ListenerErrorPolicy::Propagate);
+        let payload = catch_unwind(AssertUnwindSafe(|| store.dispatch(1))).unwrap_err();
+        assert_eq!(*payload.downcast_ref::<&str>().unwrap(), "faulty listener");
+        // state 已经提交
+        assert_eq!(store.get_state(), 1);
+        store.dispatch(2);
+        assert_eq!(*seen.borrow(), vec![(0, 1), (1, 1), (0, 2), (1, 2), (2, 2)]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn continue_others_policy_swallows_and_keeps_listener() {
+        let (store, seen, _subs) = faulty_listener(ListenerErrorPolicy::ContinueOthers);
+        store.dispatch(1);
+        store.dispatch(2);
+        assert_eq!(
+            *seen.borrow(),
+            vec![(0, 1), (1, 1), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn remove_faulty_listener_policy_unsubscribes_it() {
+        let (store, seen, _subs) = faulty_listener(ListenerErrorPolicy::RemoveFaultyListener);
+        store.dispatch(1);
+        store.dispatch(2);
+        assert_eq!(*seen.borrow(), vec![(0, 1), (1, 1), (2, 1), (0, 2), (2, 2)]);
+    }
+}
@@ -0,0 +1,83 @@
+use alloc::boxed::Box;
+
+use super::scope::ScopedStore;
+use super::store::{Listener, Store, Subscription};
+use super::storet::InternalAction;
+
+/// store 的最小公共接口：get_state / dispatch / subscribe
+/// 由 Store、ScopedStore、MockStore 实现；业务代码面向它编写，测试里换成 MockStore 即可
+/// listener 用 Box 传入，这样可以直接用 `&dyn StoreApi<S, A>`
+pub trait StoreApi<S, A> {
+    fn get_state(&self) -> S;
+    fn dispatch(&self, action: A);
+    fn subscribe(&self, listener: Box<Listener<S, A>>) -> Subscription;
+}
+
+impl<S: Clone + 'static, A: 'static> StoreApi<S, A> for Store<S, A> {
+    fn get_state(&self) -> S {
+        Store::get_state(self)
+    }
+
+    fn dispatch(&self, action: A) {
+        Store::dispatch(self, action)
+    }
+
+    fn subscribe(&self, listener: Box<Listener<S, A>>) -> Subscription {
+        Store::subscribe(self, listener)
+    }
+}
+
+impl<S: PartialEq + 'static, A: From<InternalAction> + 'static> StoreApi<S, A>
+    for ScopedStore<S, A>
+{
+    fn get_state(&self) -> S {
+        ScopedStore::get_state(self)
+    }
+
+    fn dispatch(&self, action: A) {
+        ScopedStore::dispatch(self, action)
+    }
+
+    fn subscribe(&self, listener: Box<Listener<S, A>>) -> Subscription {
+        ScopedStore::subscribe(self, listener)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    use super::*;
+
+    // 只依赖 StoreApi 的业务代码：dispatch 两次，返回 listener 看到的 state 和最后的 get_state
+    fn add_twice(store: &dyn StoreApi<i32, i32>) -> (Vec<i32>, i32) {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let _sub = store.subscribe(Box::new(move |state: &i32, _: &i32| {
+            log.borrow_mut().push(*state)
+        }));
+        store.dispatch(1);
+        store.dispatch(2);
+        let seen = seen.borrow().clone();
+        (seen, store.get_state())
+    }
+
+    #[test]
+    fn store_runs_reducer_behind_dyn_api() {
+        let store = Store::new(|state: &i32, action: &i32| state + action, 0);
+        assert_eq!(add_twice(&store), (vec![1, 3], 3));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn mock_store_records_behind_dyn_api() {
+        use crate::testing::mock::MockStore;
+
+        let store = MockStore::new(5);
+        assert_eq!(add_twice(&store), (vec![5, 5], 5));
+        store.expect_actions(&[1, 2]);
+    }
+}
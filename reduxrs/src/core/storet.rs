@@ -1,20 +1,44 @@
-use std::{
-    cell::RefCell,
-    collections::HashMap,
-    rc::Rc,
-};
+use alloc::{boxed::Box, collections::BTreeMap, rc::Rc, vec::Vec};
+use core::{any::Any, cell::RefCell};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 pub trait Action {
     fn type_(&self) -> &str;
+
+    /// Flux Standard Action 的 meta（时间戳、request id 等）；middleware 可按具体类型 downcast
+    fn meta(&self) -> Option<&dyn Any> {
+        None
+    }
+
+    /// Flux Standard Action 的 error 标记：payload 是一个错误
+    fn is_error(&self) -> bool {
+        false
+    }
+
+    /// 低优先级：可以被 auto batch 合并通知（等价 RTK 的 meta[SHOULD_AUTOBATCH]）
+    fn should_autobatch(&self) -> bool {
+        false
+    }
+
+    /// 每个 action 唯一的 id（通常放在 meta 里），用于去重（见 Store::enable_dedup）
+    fn id(&self) -> Option<&str> {
+        None
+    }
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum InternalActionType {
     Init,
     Replace,
+    /// Store::restore 强制替换 state 后发出
+    Restore,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct InternalAction {
     pub kind: InternalActionType,
 }
@@ -23,10 +47,17 @@ impl Action for InternalAction {
         match self.kind {
             InternalActionType::Init => "@@redux/INIT",
             InternalActionType::Replace => "@@redux/REPLACE",
+            InternalActionType::Restore => "@@redux/RESTORE",
         }
     }
 }
 
+impl<B> From<InternalAction> for AppAction<B> {
+    fn from(action: InternalAction) -> Self {
+        AppAction::Internal(action)
+    }
+}
+
 /// 你的业务 action 示例：你可以替换成自己的 enum/struct
 #[derive(Clone, Debug)]
 pub enum CounterAction {
@@ -44,6 +75,7 @@ impl Action for CounterAction {
 
 /// AppAction = Internal + Business（像 TS 里 `as A` 的安全替代）
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AppAction<B> {
     Internal(InternalAction),
     Business(B),
@@ -55,6 +87,34 @@ impl<B: Action> Action for AppAction<B> {
             AppAction::Business(b) => b.type_(),
         }
     }
+
+    fn meta(&self) -> Option<&dyn Any> {
+        match self {
+            AppAction::Internal(a) => a.meta(),
+            AppAction::Business(b) => b.meta(),
+        }
+    }
+
+    fn is_error(&self) -> bool {
+        match self {
+            AppAction::Internal(a) => a.is_error(),
+            AppAction::Business(b) => b.is_error(),
+        }
+    }
+
+    fn should_autobatch(&self) -> bool {
+        match self {
+            AppAction::Internal(a) => a.should_autobatch(),
+            AppAction::Business(b) => b.should_autobatch(),
+        }
+    }
+
+    fn id(&self) -> Option<&str> {
+        match self {
+            AppAction::Internal(a) => a.id(),
+            AppAction::Business(b) => b.id(),
+        }
+    }
 }
 
 pub type Reducer<S, A> = dyn Fn(Option<S>, &A) -> S;
@@ -82,7 +142,7 @@ impl<S, A: Action + Clone> UnsubscribeHandle<S, A> {
 struct StoreInner<S, A: Action> {
     reducer: RefCell<Box<Reducer<S, A>>>,
     state: RefCell<Option<S>>,
-    listeners: RefCell<HashMap<usize, Rc<dyn Fn()>>>,
+    listeners: RefCell<BTreeMap<usize, Rc<dyn Fn()>>>,
     next_listener_id: RefCell<usize>,
     is_dispatching: RefCell<bool>,
 }
@@ -93,7 +153,7 @@ impl<S: Clone + 'static, A: Action + Clone + 'static> Store<S, A> {
             inner: Rc::new(StoreInner {
                 reducer: RefCell::new(reducer),
                 state: RefCell::new(preloaded_state),
-                listeners: RefCell::new(HashMap::new()),
+                listeners: RefCell::new(BTreeMap::new()),
                 next_listener_id: RefCell::new(0),
                 is_dispatching: RefCell::new(false),
             }),
@@ -142,26 +202,40 @@ impl<S: Clone + 'static, A: Action + Clone + 'static> Store<S, A> {
         self.dispatch(replace_action);
     }
 
-    pub fn subscribe_state<F>(&self, mut observer: F) -> UnsubscribeHandle<S, A>
+    pub fn subscribe_state<F>(&self, observer: F) -> UnsubscribeHandle<S, A>
     where
         F: FnMut(S) + 'static,
     {
-        observer(self.get_state());
+        let observer = RefCell::new(observer);
+        (observer.borrow_mut())(self.get_state());
         let store = self.clone();
-        self.subscribe(move || observer(store.get_state()))
+        self.subscribe(move || (observer.borrow_mut())(store.get_state()))
     }
 }
 
-impl<S: Clone, A: Action> StoreInner<S, A> {
+// reducer panic 时也复位 is_dispatching（state 已被拿走，不做恢复）
+struct ReducingGuard<'a>(&'a RefCell<bool>);
+
+impl Drop for ReducingGuard<'_> {
+    fn drop(&mut self) {
+        *self.0.borrow_mut() = false;
+    }
+}
+
+impl<S, A: Action> StoreInner<S, A> {
     fn assert_not_dispatching(&self, what: &str) {
         if *self.is_dispatching.borrow() {
-            panic!(
-                "You may not call {} while the reducer is executing.",
-                what
-            );
+            panic!("You may not call {} while the reducer is executing.", what);
         }
     }
 
+    fn unsubscribe(&self, id: usize) {
+        self.assert_not_dispatching("unsubscribe()");
+        self.listeners.borrow_mut().remove(&id);
+    }
+}
+
+impl<S: Clone, A: Action> StoreInner<S, A> {
     fn get_state(&self) -> S {
         self.assert_not_dispatching("store.get_state()");
         self.state
@@ -171,11 +245,6 @@ impl<S: Clone, A: Action> StoreInner<S, A> {
             .clone()
     }
 
-    fn unsubscribe(&self, id: usize) {
-        self.assert_not_dispatching("unsubscribe()");
-        self.listeners.borrow_mut().remove(&id);
-    }
-
     fn dispatch(&self, action: A) {
         let t = action.type_();
         if t.is_empty() {
@@ -187,10 +256,10 @@ impl<S: Clone, A: Action> StoreInner<S, A> {
 
         {
             *self.is_dispatching.borrow_mut() = true;
+            let _guard = ReducingGuard(&self.is_dispatching);
             let prev = self.state.borrow_mut().take();
             let next = (self.reducer.borrow())(prev, &action);
             *self.state.borrow_mut() = Some(next);
-            *self.is_dispatching.borrow_mut() = false;
         }
 
         // snapshot
@@ -231,3 +300,44 @@ pub fn example_counter_store() -> Store<i32, AppAction<CounterAction>> {
 
     Store::new(reducer, None, init)
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    #[cfg(feature = "std")]
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    use super::*;
+
+    #[test]
+    fn listener_reads_state() {
+        let store = example_counter_store();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (reader, log) = (store.clone(), seen.clone());
+        let _handle = store.subscribe(move || log.borrow_mut().push(reader.get_state()));
+        store.dispatch(AppAction::Business(CounterAction::Inc));
+        store.dispatch(AppAction::Business(CounterAction::Inc));
+        assert_eq!(*seen.borrow(), vec![1, 2]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn reducer_panic_resets_dispatching_flag() {
+        let reducer = Box::new(|state: Option<i32>, action: &AppAction<CounterAction>| -> i32 {
+            match action {
+                AppAction::Business(CounterAction::Dec) => panic!("no dec"),
+                _ => state.unwrap_or(0),
+            }
+        });
+        let init = AppAction::Internal(InternalAction {
+            kind: InternalActionType::Init,
+        });
+        let store = Store::new(reducer, None, init);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            store.dispatch(AppAction::Business(CounterAction::Dec))
+        }));
+        assert!(result.is_err());
+        // 不会再报 “while the reducer is executing”
+        store.subscribe(|| {}).unsubscribe();
+    }
+}
@@ -0,0 +1,189 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "tokio")]
+pub mod tokio_timer;
+#[cfg(feature = "wasm")]
+pub mod wasm_timer;
+
+pub type TimerId = u64;
+
+/// 单调时钟，起点由实现决定
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+/// 可插拔的定时器：StdTimer / TokioTimer / WasmTimer / 游戏主循环各自实现，测试里用 ManualTimer
+/// 回调在定时器所在的线程上执行（store 不是 Send）
+pub trait Timer: Clock {
+    fn set_timeout(&self, delay: Duration, callback: Box<dyn FnOnce()>) -> TimerId;
+    /// 取消尚未触发的回调；已触发或不存在的 id 忽略
+    fn clear_timeout(&self, id: TimerId);
+}
+
+// ManualTimer / StdTimer 共用的到期队列
+#[derive(Default)]
+struct TimerQueue {
+    next_id: TimerId,
+    // (到期时间, id) 排序；同一时刻按注册顺序
+    queue: BTreeMap<(Duration, TimerId), Box<dyn FnOnce()>>,
+}
+
+impl TimerQueue {
+    fn insert(&mut self, due: Duration, callback: Box<dyn FnOnce()>) -> TimerId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.queue.insert((due, id), callback);
+        id
+    }
+
+    fn remove(&mut self, id: TimerId) {
+        self.queue.retain(|&(_, i), _| i != id);
+    }
+
+    fn next_due(&self) -> Option<Duration> {
+        self.queue.first_key_value().map(|(&(due, _), _)| due)
+    }
+
+    fn pop_due(&mut self, now: Duration) -> Option<(Duration, Box<dyn FnOnce()>)> {
+        if self.next_due()? > now {
+            return None;
+        }
+        self.queue.pop_first().map(|((due, _), cb)| (due, cb))
+    }
+}
+
+/// 手动推进的定时器：advance 时按到期顺序执行回调，测试里时间完全确定
+#[derive(Clone, Default)]
+pub struct ManualTimer {
+    inner: Rc<RefCell<ManualInner>>,
+}
+
+#[derive(Default)]
+struct ManualInner {
+    now: Duration,
+    queue: TimerQueue,
+}
+
+impl ManualTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 时间前进 by，期间到期的回调（包括回调里新注册且同样到期的）依次执行
+    pub fn advance(&self, by: Duration) {
+        let target = self.inner.borrow().now + by;
+        loop {
+            let callback = {
+                let mut inner = self.inner.borrow_mut();
+                inner.queue.pop_due(target).map(|(due, cb)| {
+                    inner.now = due;
+                    cb
+                })
+            };
+            match callback {
+                Some(callback) => callback(),
+                None => break,
+            }
+        }
+        self.inner.borrow_mut().now = target;
+    }
+
+    /// 尚未触发的回调数量
+    pub fn pending(&self) -> usize {
+        self.inner.borrow().queue.queue.len()
+    }
+}
+
+impl Clock for ManualTimer {
+    fn now(&self) -> Duration {
+        self.inner.borrow().now
+    }
+}
+
+impl Timer for ManualTimer {
+    fn set_timeout(&self, delay: Duration, callback: Box<dyn FnOnce()>) -> TimerId {
+        let mut inner = self.inner.borrow_mut();
+        let due = inner.now + delay;
+        inner.queue.insert(due, callback)
+    }
+
+    fn clear_timeout(&self, id: TimerId) {
+        self.inner.borrow_mut().queue.remove(id);
+    }
+}
+
+/// 基于 std::time::Instant 的真实定时器，没有后台线程：
+/// 由应用主循环调用 run_due（或 run_until_idle）执行到期的回调
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct StdTimer {
+    start: Instant,
+    queue: Rc<RefCell<TimerQueue>>,
+}
+
+#[cfg(feature = "std")]
+impl Default for StdTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl StdTimer {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            queue: Rc::new(RefCell::new(TimerQueue::default())),
+        }
+    }
+
+    /// 执行所有已到期的回调
+    pub fn run_due(&self) {
+        loop {
+            let callback = self.queue.borrow_mut().pop_due(self.now());
+            match callback {
+                Some((_, callback)) => callback(),
+                None => break,
+            }
+        }
+    }
+
+    /// 距离下一个回调到期还有多久；没有待执行的回调时为 None
+    pub fn next_deadline(&self) -> Option<Duration> {
+        let due = self.queue.borrow().next_due()?;
+        Some(due.saturating_sub(self.now()))
+    }
+
+    /// 阻塞当前线程，依次执行回调，直到队列为空
+    pub fn run_until_idle(&self) {
+        while let Some(wait) = self.next_deadline() {
+            std::thread::sleep(wait);
+            self.run_due();
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdTimer {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Timer for StdTimer {
+    fn set_timeout(&self, delay: Duration, callback: Box<dyn FnOnce()>) -> TimerId {
+        let due = self.now() + delay;
+        self.queue.borrow_mut().insert(due, callback)
+    }
+
+    fn clear_timeout(&self, id: TimerId) {
+        self.queue.borrow_mut().remove(id);
+    }
+}
@@ -0,0 +1,68 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use super::{Clock, Timer, TimerId};
+
+/// tokio 上的定时器：回调通过 spawn_local 执行，需要在 LocalSet 中使用
+/// now 使用 tokio 的时钟，测试里 `start_paused` + `tokio::time::advance` 即可确定推进
+#[derive(Clone)]
+pub struct TokioTimer {
+    start: Instant,
+    inner: Rc<RefCell<TokioInner>>,
+}
+
+#[derive(Default)]
+struct TokioInner {
+    next_id: TimerId,
+    handles: HashMap<TimerId, JoinHandle<()>>,
+}
+
+impl Default for TokioTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokioTimer {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            inner: Rc::new(RefCell::new(TokioInner::default())),
+        }
+    }
+}
+
+impl Clock for TokioTimer {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Timer for TokioTimer {
+    fn set_timeout(&self, delay: Duration, callback: Box<dyn FnOnce()>) -> TimerId {
+        let mut inner = self.inner.borrow_mut();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let weak = Rc::downgrade(&self.inner);
+        let handle = tokio::task::spawn_local(async move {
+            tokio::time::sleep(delay).await;
+            if let Some(inner) = weak.upgrade() {
+                inner.borrow_mut().handles.remove(&id);
+            }
+            callback();
+        });
+        inner.handles.insert(id, handle);
+        id
+    }
+
+    fn clear_timeout(&self, id: TimerId) {
+        if let Some(handle) = self.inner.borrow_mut().handles.remove(&id) {
+            handle.abort();
+        }
+    }
+}
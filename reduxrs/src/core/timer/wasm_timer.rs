@@ -0,0 +1,83 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+
+use super::{Clock, Timer, TimerId};
+
+/// 基于 JS 全局 setTimeout / clearTimeout 的定时器（浏览器和 Node 都可用）
+#[derive(Clone)]
+pub struct WasmTimer {
+    start_ms: f64,
+    // JS 的 timeout 句柄在 Node 里是对象，所以另外分配 TimerId
+    inner: Rc<RefCell<WasmInner>>,
+}
+
+#[derive(Default)]
+struct WasmInner {
+    next_id: TimerId,
+    handles: HashMap<TimerId, JsValue>,
+}
+
+impl Default for WasmTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WasmTimer {
+    pub fn new() -> Self {
+        Self {
+            start_ms: js_sys::Date::now(),
+            inner: Rc::new(RefCell::new(WasmInner::default())),
+        }
+    }
+}
+
+fn global_function(name: &str) -> js_sys::Function {
+    js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str(name))
+        .ok()
+        .and_then(|f| f.dyn_into().ok())
+        .unwrap_or_else(|| panic!("The JS global `{}` is not available.", name))
+}
+
+impl Clock for WasmTimer {
+    fn now(&self) -> Duration {
+        Duration::from_secs_f64(((js_sys::Date::now() - self.start_ms) / 1000.0).max(0.0))
+    }
+}
+
+impl Timer for WasmTimer {
+    fn set_timeout(&self, delay: Duration, callback: Box<dyn FnOnce()>) -> TimerId {
+        let id = {
+            let mut inner = self.inner.borrow_mut();
+            inner.next_id += 1;
+            inner.next_id
+        };
+        let weak = Rc::downgrade(&self.inner);
+        let closure = Closure::once_into_js(move || {
+            if let Some(inner) = weak.upgrade() {
+                inner.borrow_mut().handles.remove(&id);
+            }
+            callback();
+        });
+        let handle = global_function("setTimeout")
+            .call2(
+                &JsValue::NULL,
+                &closure,
+                &JsValue::from_f64(delay.as_secs_f64() * 1000.0),
+            )
+            .expect("setTimeout threw an exception.");
+        self.inner.borrow_mut().handles.insert(id, handle);
+        id
+    }
+
+    fn clear_timeout(&self, id: TimerId) {
+        if let Some(handle) = self.inner.borrow_mut().handles.remove(&id) {
+            let _ = global_function("clearTimeout").call1(&JsValue::NULL, &handle);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use super::store::{Store, Subscription, Version};
+
+type OnChange<T> = dyn FnMut(&T);
+
+type Callbacks<T> = Rc<RefCell<Vec<Rc<RefCell<Box<OnChange<T>>>>>>>;
+
+struct WatchState<T> {
+    value: T,
+    // 值最后一次变化时 store 的 version
+    version: Version,
+}
+
+/// Store::watch 返回的句柄：自己持有最新的派生值，适合缓存、索引、导出等非 UI 消费者
+/// Drop 时自动退订
+pub struct Watched<T> {
+    state: Rc<RefCell<WatchState<T>>>,
+    callbacks: Callbacks<T>,
+    _subscription: Subscription,
+}
+
+impl<T: PartialEq + 'static> Watched<T> {
+    pub(crate) fn from_store<S: 'static, A: 'static>(
+        store: &Store<S, A>,
+        selector: impl Fn(&S) -> T + 'static,
+    ) -> Self {
+        let state = Rc::new(RefCell::new(WatchState {
+            value: store.with_state(&selector),
+            version: store.version(),
+        }));
+        let callbacks: Callbacks<T> = Rc::new(RefCell::new(Vec::new()));
+
+        let (watch_state, watch_callbacks) = (state.clone(), callbacks.clone());
+        let subscription = store.subscribe_versioned(move |s, _, version| {
+            let next = selector(s);
+            {
+                let mut current = watch_state.borrow_mut();
+                if current.value == next {
+                    return;
+                }
+                current.value = next;
+                current.version = version;
+            }
+            // 先取快照：回调里可以再调用 on_change（从下一次变化开始生效）
+            let callbacks = watch_callbacks.borrow().clone();
+            let current = watch_state.borrow();
+            for callback in callbacks {
+                (callback.borrow_mut())(&current.value);
+            }
+        });
+
+        Self {
+            state,
+            callbacks,
+            _subscription: subscription,
+        }
+    }
+}
+
+impl<T> Watched<T> {
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.state.borrow().value.clone()
+    }
+
+    /// 不克隆，直接在 borrow 内读取
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.state.borrow().value)
+    }
+
+    /// 值最后一次变化时的 store version
+    pub fn version(&self) -> Version {
+        self.state.borrow().version
+    }
+
+    /// 自 version 之后值是否变化过（传入上次读取时的 Watched::version）
+    pub fn changed_since(&self, version: Version) -> bool {
+        self.state.borrow().version > version
+    }
+
+    /// 值变化后回调（收到新值）
+    pub fn on_change(&self, callback: impl FnMut(&T) + 'static) {
+        self.callbacks
+            .borrow_mut()
+            .push(Rc::new(RefCell::new(Box::new(callback))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn store() -> Store<(i32, i32), (i32, i32)> {
+        Store::new(
+            |state: &(i32, i32), action: &(i32, i32)| (state.0 + action.0, state.1 + action.1),
+            (0, 0),
+        )
+    }
+
+    #[test]
+    fn on_change_fires_only_when_selection_changes() {
+        let store = store();
+        let watched = store.watch(|state: &(i32, i32)| state.0);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        watched.on_change(move |value| log.borrow_mut().push(*value));
+        store.dispatch((1, 0));
+        store.dispatch((0, 1));
+        let before = watched.version();
+        store.dispatch((2, 0));
+        assert_eq!(*seen.borrow(), vec![1, 3]);
+        assert_eq!(watched.get(), 3);
+        assert_eq!(watched.version(), 3);
+        assert!(watched.changed_since(before));
+        assert!(!watched.changed_since(watched.version()));
+    }
+
+    #[test]
+    fn on_change_can_register_from_callback() {
+        let store = store();
+        let watched = Rc::new(store.watch(|state: &(i32, i32)| state.0));
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (weak, log) = (Rc::downgrade(&watched), seen.clone());
+        watched.on_change(move |value| {
+            let Some(watched) = weak.upgrade() else {
+                return;
+            };
+            let log = log.clone();
+            let registered = *value;
+            watched.on_change(move |value| log.borrow_mut().push((registered, *value)));
+        });
+        store.dispatch((1, 0));
+        // 新注册的回调不参与注册它的这一次变化
+        assert!(seen.borrow().is_empty());
+        store.dispatch((1, 0));
+        assert_eq!(*seen.borrow(), vec![(1, 2)]);
+    }
+}
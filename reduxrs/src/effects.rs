@@ -0,0 +1,4 @@
+pub mod abort;
+pub mod race;
+pub mod runtime;
+pub mod task;
\ No newline at end of file
@@ -0,0 +1,173 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+type AbortCallback = Box<dyn FnOnce(&str)>;
+
+#[derive(Default)]
+struct SignalInner {
+    reason: Option<String>,
+    wakers: Vec<Waker>,
+    callbacks: Vec<AbortCallback>,
+}
+
+/// 等价 JS 的 AbortController：持有者调用 abort，持有 signal 的异步工作随之取消
+#[derive(Default)]
+pub struct AbortController {
+    signal: AbortSignal,
+}
+
+impl AbortController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn signal(&self) -> AbortSignal {
+        self.signal.clone()
+    }
+
+    /// 只有第一次 abort 生效
+    pub fn abort(&self, reason: impl Into<String>) {
+        let (wakers, callbacks) = {
+            let mut inner = self.signal.inner.borrow_mut();
+            if inner.reason.is_some() {
+                return;
+            }
+            inner.reason = Some(reason.into());
+            (
+                std::mem::take(&mut inner.wakers),
+                std::mem::take(&mut inner.callbacks),
+            )
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+        let reason = self.signal.reason().unwrap_or_default();
+        for callback in callbacks {
+            callback(&reason);
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct AbortSignal {
+    inner: Rc<RefCell<SignalInner>>,
+}
+
+impl AbortSignal {
+    pub fn is_aborted(&self) -> bool {
+        self.inner.borrow().reason.is_some()
+    }
+
+    pub fn reason(&self) -> Option<String> {
+        self.inner.borrow().reason.clone()
+    }
+
+    /// abort 时回调；已经 abort 的话立即调用
+    pub fn on_abort(&self, callback: impl FnOnce(&str) + 'static) {
+        let reason = {
+            let mut inner = self.inner.borrow_mut();
+            match inner.reason.clone() {
+                Some(reason) => reason,
+                None => {
+                    inner.callbacks.push(Box::new(callback));
+                    return;
+                }
+            }
+        };
+        callback(&reason);
+    }
+
+    fn poll_aborted(&self, cx: &mut Context<'_>) -> Poll<Aborted> {
+        let mut inner = self.inner.borrow_mut();
+        match &inner.reason {
+            Some(reason) => Poll::Ready(Aborted {
+                reason: reason.clone(),
+            }),
+            None => {
+                // 同一个任务反复 poll 时只留一份 waker
+                if !inner.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                    inner.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// 被 abort 的异步工作的结果
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Aborted {
+    pub reason: String,
+}
+
+impl fmt::Display for Aborted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "aborted: {}", self.reason)
+    }
+}
+
+impl std::error::Error for Aborted {}
+
+/// 给 future 套上 signal：abort 后 future 被 drop，得到 Err(Aborted)
+pub fn abortable<F: Future>(signal: &AbortSignal, future: F) -> Abortable<F> {
+    Abortable {
+        signal: signal.clone(),
+        future: Box::pin(future),
+    }
+}
+
+pub struct Abortable<F> {
+    signal: AbortSignal,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Abortable<F> {
+    type Output = Result<F::Output, Aborted>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        // 先看 signal：已经 abort 的工作不再推进
+        if let Poll::Ready(aborted) = this.signal.poll_aborted(cx) {
+            return Poll::Ready(Err(aborted));
+        }
+        this.future.as_mut().poll(cx).map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::pending;
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    use super::*;
+
+    struct NoopWake;
+
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[test]
+    fn repeated_polls_keep_one_waker() {
+        let controller = AbortController::new();
+        let mut task = abortable(&controller.signal(), pending::<()>());
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+        for _ in 0..100 {
+            assert!(Pin::new(&mut task).poll(&mut cx).is_pending());
+        }
+        assert_eq!(controller.signal.inner.borrow().wakers.len(), 1);
+        controller.abort("stop");
+        assert_eq!(
+            Pin::new(&mut task).poll(&mut cx),
+            Poll::Ready(Err(Aborted {
+                reason: "stop".to_string()
+            }))
+        );
+    }
+}
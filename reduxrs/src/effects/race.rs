@@ -0,0 +1,37 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+/// 等价 saga 的 race：先完成的一方胜出，另一方被 drop（即取消）
+pub fn race<L: Future, R: Future>(left: L, right: R) -> Race<L, R> {
+    Race {
+        left: Box::pin(left),
+        right: Box::pin(right),
+    }
+}
+
+pub struct Race<L, R> {
+    left: Pin<Box<L>>,
+    right: Pin<Box<R>>,
+}
+
+impl<L: Future, R: Future> Future for Race<L, R> {
+    type Output = Either<L::Output, R::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if let Poll::Ready(value) = this.left.as_mut().poll(cx) {
+            return Poll::Ready(Either::Left(value));
+        }
+        if let Poll::Ready(value) = this.right.as_mut().poll(cx) {
+            return Poll::Ready(Either::Right(value));
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,537 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+use super::abort::{AbortSignal, Aborted, abortable};
+use super::task::{CancelTask, TaskHandle, TaskId, TaskState};
+use crate::core::middleware::{MiddlewareApi, Next};
+use crate::core::store::Store;
+
+type LocalFuture = Pin<Box<dyn Future<Output = ()>>>;
+type ReadyQueue = Arc<Mutex<VecDeque<TaskId>>>;
+
+/// saga 的运行时：单线程执行器 + store 的 action 通道
+///
+/// 创建时向 store 挂一个 middleware，每次 dispatch 之后自动推进被唤醒的任务；
+/// 如果 saga 里 await 了外部 future（定时器、网络），它们唤醒之后需要调用 run_until_stalled
+pub struct SagaRuntime<S, A> {
+    shared: Rc<Shared<S, A>>,
+}
+
+pub(crate) struct Shared<S, A> {
+    store: Store<S, A>,
+    tasks: RefCell<HashMap<TaskId, TaskEntry>>,
+    next_id: Cell<TaskId>,
+    // 唤醒可能来自其他线程，所以队列是 Arc<Mutex>
+    ready: ReadyQueue,
+    takers: RefCell<Vec<Taker<A>>>,
+    running: Cell<bool>,
+}
+
+struct TaskEntry {
+    future: Option<LocalFuture>,
+    // 每个任务一个 waker，反复 poll 时 will_wake 成立，AbortSignal / Join 不会重复登记
+    waker: Waker,
+    state: Rc<TaskState>,
+    children: Vec<TaskId>,
+}
+
+struct Taker<A> {
+    matcher: Rc<dyn Fn(&A) -> bool>,
+    // Take future 被 drop 后这里 upgrade 失败，顺便清理
+    slot: Weak<RefCell<Option<A>>>,
+    waker: Waker,
+}
+
+struct TaskWaker {
+    id: TaskId,
+    ready: ReadyQueue,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.ready.lock().unwrap().push_back(self.id);
+    }
+}
+
+impl<S: Clone + 'static, A: Clone + 'static> SagaRuntime<S, A> {
+    pub fn new(store: &Store<S, A>) -> Self {
+        let shared = Rc::new(Shared {
+            store: store.clone(),
+            tasks: RefCell::new(HashMap::new()),
+            next_id: Cell::new(0),
+            ready: Arc::new(Mutex::new(VecDeque::new())),
+            takers: RefCell::new(Vec::new()),
+            running: Cell::new(false),
+        });
+        let weak = Rc::downgrade(&shared);
+        // 和 redux-saga 一样挂成 middleware：action 先交给 reducer，再喂给 saga；
+        // 任务里 put 产生的嵌套 dispatch 不会重入 listener
+        // runtime drop 之后 middleware 只做透传
+        store.apply_middleware(
+            move |_: &dyn MiddlewareApi<S, A>, action: A, next: Next<A>| {
+                next(action.clone());
+                if let Some(shared) = weak.upgrade() {
+                    shared.offer(&action);
+                }
+            },
+        );
+        Self { shared }
+    }
+
+    /// 启动一个顶层 saga
+    pub fn spawn<T, F, Fut>(&self, saga: F) -> TaskHandle<T>
+    where
+        T: 'static,
+        F: FnOnce(Effects<S, A>) -> Fut,
+        Fut: Future<Output = T> + 'static,
+    {
+        self.shared.spawn(None, saga)
+    }
+
+    /// 推进所有已被唤醒的任务，直到没有可执行的为止
+    pub fn run_until_stalled(&self) {
+        self.shared.run_until_stalled();
+    }
+}
+
+impl<S: Clone + 'static, A: Clone + 'static> Shared<S, A> {
+    fn spawn<T, F, Fut>(self: &Rc<Self>, parent: Option<TaskId>, saga: F) -> TaskHandle<T>
+    where
+        T: 'static,
+        F: FnOnce(Effects<S, A>) -> Fut,
+        Fut: Future<Output = T> + 'static,
+    {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+
+        let state = Rc::new(TaskState::default());
+        let result = Rc::new(RefCell::new(None));
+        let future = saga(Effects {
+            shared: Rc::downgrade(self),
+            task: id,
+        });
+        let slot = result.clone();
+        let wrapped: LocalFuture = Box::pin(async move {
+            *slot.borrow_mut() = Some(future.await);
+        });
+
+        {
+            let mut tasks = self.tasks.borrow_mut();
+            tasks.insert(
+                id,
+                TaskEntry {
+                    future: Some(wrapped),
+                    waker: Waker::from(Arc::new(TaskWaker {
+                        id,
+                        ready: self.ready.clone(),
+                    })),
+                    state: state.clone(),
+                    children: Vec::new(),
+                },
+            );
+            if let Some(parent) = parent.and_then(|p| tasks.get_mut(&p)) {
+                parent.children.push(id);
+            }
+        }
+        self.ready.lock().unwrap().push_back(id);
+        self.run_until_stalled();
+
+        let runtime: Rc<dyn CancelTask> = self.clone();
+        TaskHandle {
+            id,
+            state,
+            result,
+            runtime: Rc::downgrade(&runtime),
+        }
+    }
+
+    // action 提交之后：喂给等待中的 take，然后推进被唤醒的任务
+    fn offer(&self, action: &A) {
+        self.takers.borrow_mut().retain(|taker| {
+            let Some(slot) = taker.slot.upgrade() else {
+                return false;
+            };
+            if !(taker.matcher)(action) {
+                return true;
+            }
+            *slot.borrow_mut() = Some(action.clone());
+            taker.waker.wake_by_ref();
+            false
+        });
+        self.run_until_stalled();
+    }
+
+    fn run_until_stalled(&self) {
+        // 在任务内部 put 触发的 dispatch 会回到这里；外层循环会继续处理
+        if self.running.replace(true) {
+            return;
+        }
+        let _guard = RunningGuard(&self.running);
+        loop {
+            let next = self.ready.lock().unwrap().pop_front();
+            match next {
+                Some(id) => self.poll_task(id),
+                None => break,
+            }
+        }
+    }
+
+    fn poll_task(&self, id: TaskId) {
+        // 取出 future 再 poll：任务内部 fork / cancel 需要修改 tasks
+        let (mut future, waker, state) = {
+            let mut tasks = self.tasks.borrow_mut();
+            let Some(entry) = tasks.get_mut(&id) else {
+                return;
+            };
+            let Some(future) = entry.future.take() else {
+                return;
+            };
+            (future, entry.waker.clone(), entry.state.clone())
+        };
+
+        match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(()) => {
+                self.tasks.borrow_mut().remove(&id);
+                state.finish();
+            }
+            Poll::Pending => {
+                // poll 期间被取消：entry 已经移除，future 在这里 drop
+                if let Some(entry) = self.tasks.borrow_mut().get_mut(&id) {
+                    entry.future = Some(future);
+                }
+            }
+        }
+    }
+}
+
+impl<S: Clone + 'static, A: Clone + 'static> CancelTask for Shared<S, A> {
+    fn cancel_task(&self, id: TaskId) {
+        self.cancel_tree(id);
+        // 推进 join 了被取消任务的等待者
+        self.run_until_stalled();
+    }
+}
+
+impl<S, A> Shared<S, A> {
+    fn cancel_tree(&self, id: TaskId) {
+        let Some(entry) = self.tasks.borrow_mut().remove(&id) else {
+            return;
+        };
+        entry.state.cancel();
+        // 在 borrow 之外 drop future，future 的析构可能再访问 runtime
+        drop(entry.future);
+        for child in entry.children {
+            self.cancel_tree(child);
+        }
+    }
+}
+
+struct RunningGuard<'a>(&'a Cell<bool>);
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+/// saga 内部可用的 effect（take / put / select / call / fork）
+pub struct Effects<S, A> {
+    shared: Weak<Shared<S, A>>,
+    task: TaskId,
+}
+
+impl<S, A> Clone for Effects<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            task: self.task,
+        }
+    }
+}
+
+impl<S: Clone + 'static, A: Clone + 'static> Effects<S, A> {
+    /// 等待下一个满足 matcher 的 action（不缓冲：等待开始之前的 action 不算）
+    pub fn take(&self, matcher: impl Fn(&A) -> bool + 'static) -> Take<S, A> {
+        Take {
+            shared: self.shared.clone(),
+            matcher: Rc::new(matcher),
+            slot: Rc::new(RefCell::new(None)),
+            registered: false,
+        }
+    }
+
+    /// dispatch 一个 action（经过完整的 middleware 链）
+    pub fn put(&self, action: A) {
+        if let Some(shared) = self.shared.upgrade() {
+            shared.store.dispatch(action);
+        }
+    }
+
+    pub fn select<T>(&self, selector: impl FnOnce(&S) -> T) -> T {
+        let shared = self
+            .shared
+            .upgrade()
+            .expect("SagaRuntime was dropped while a saga was still running.");
+        shared.store.with_state(selector)
+    }
+
+    /// 等价 saga 的 call：等待一个 future；任务被取消时它会随任务一起 drop
+    pub async fn call<F: Future>(&self, future: F) -> F::Output {
+        future.await
+    }
+
+    /// 带取消的 call：signal 被 abort 时 put(rejected(aborted)) 并返回 None
+    pub async fn call_abortable<F: Future>(
+        &self,
+        signal: &AbortSignal,
+        future: F,
+        rejected: impl FnOnce(Aborted) -> A,
+    ) -> Option<F::Output> {
+        match abortable(signal, future).await {
+            Ok(value) => Some(value),
+            Err(aborted) => {
+                self.put(rejected(aborted));
+                None
+            }
+        }
+    }
+
+    /// 启动一个子任务；父任务被取消时子任务一并取消
+    pub fn fork<T, F, Fut>(&self, saga: F) -> TaskHandle<T>
+    where
+        T: 'static,
+        F: FnOnce(Effects<S, A>) -> Fut,
+        Fut: Future<Output = T> + 'static,
+    {
+        let shared = self
+            .shared
+            .upgrade()
+            .expect("SagaRuntime was dropped while a saga was still running.");
+        shared.spawn(Some(self.task), saga)
+    }
+}
+
+pub struct Take<S, A> {
+    shared: Weak<Shared<S, A>>,
+    matcher: Rc<dyn Fn(&A) -> bool>,
+    slot: Rc<RefCell<Option<A>>>,
+    registered: bool,
+}
+
+impl<S, A> Future for Take<S, A> {
+    type Output = A;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<A> {
+        let this = self.get_mut();
+        if let Some(action) = this.slot.borrow_mut().take() {
+            return Poll::Ready(action);
+        }
+        if !this.registered {
+            if let Some(shared) = this.shared.upgrade() {
+                shared.takers.borrow_mut().push(Taker {
+                    matcher: this.matcher.clone(),
+                    slot: Rc::downgrade(&this.slot),
+                    waker: cx.waker().clone(),
+                });
+            }
+            this.registered = true;
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::abort::AbortController;
+    use crate::effects::race::{Either, race};
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Act {
+        Ping,
+        Pong,
+        Stop,
+        Failed(String),
+    }
+
+    fn store() -> Store<Vec<Act>, Act> {
+        Store::new(
+            |state: &Vec<Act>, action: &Act| {
+                let mut next = state.clone();
+                next.push(action.clone());
+                next
+            },
+            Vec::new(),
+        )
+    }
+
+    fn pongs(store: &Store<Vec<Act>, Act>) -> usize {
+        store.with_state(|state| state.iter().filter(|a| **a == Act::Pong).count())
+    }
+
+    #[test]
+    fn take_only_sees_actions_after_it_starts_waiting() {
+        let store = store();
+        let runtime = SagaRuntime::new(&store);
+        store.dispatch(Act::Ping);
+        let handle = runtime.spawn(|fx| async move {
+            loop {
+                fx.take(|a| *a == Act::Ping).await;
+                fx.put(Act::Pong);
+            }
+        });
+        assert_eq!(pongs(&store), 0);
+        store.dispatch(Act::Ping);
+        store.dispatch(Act::Stop);
+        store.dispatch(Act::Ping);
+        assert_eq!(
+            store.get_state(),
+            vec![
+                Act::Ping,
+                Act::Ping,
+                Act::Pong,
+                Act::Stop,
+                Act::Ping,
+                Act::Pong
+            ]
+        );
+        assert!(handle.is_running());
+    }
+
+    #[test]
+    fn join_yields_result_or_none_when_cancelled() {
+        let store = store();
+        let runtime = SagaRuntime::new(&store);
+        let finished = runtime.spawn(|fx| async move {
+            fx.take(|a| *a == Act::Stop).await;
+            fx.select(|state| state.len())
+        });
+        let stuck = runtime.spawn(|fx| async move { fx.take(|a| *a == Act::Pong).await });
+        let results = Rc::new(RefCell::new(Vec::new()));
+        let sink = results.clone();
+        let (finished_join, stuck_join) = (finished.join(), stuck.join());
+        runtime.spawn(move |_| async move {
+            let finished = finished_join.await;
+            sink.borrow_mut().push(finished);
+            let stuck = stuck_join.await.map(|_| 0);
+            sink.borrow_mut().push(stuck);
+        });
+
+        store.dispatch(Act::Stop);
+        assert!(!finished.is_running());
+        assert_eq!(*results.borrow(), vec![Some(1)]);
+        stuck.cancel();
+        assert!(stuck.is_cancelled());
+        assert_eq!(*results.borrow(), vec![Some(1), None]);
+    }
+
+    #[test]
+    fn cancelling_parent_cancels_forked_children() {
+        let store = store();
+        let runtime = SagaRuntime::new(&store);
+        let child = Rc::new(RefCell::new(None));
+        let slot = child.clone();
+        let parent = runtime.spawn(move |fx| async move {
+            *slot.borrow_mut() = Some(fx.fork(|fx| async move {
+                loop {
+                    fx.take(|a| *a == Act::Ping).await;
+                    fx.put(Act::Pong);
+                }
+            }));
+            fx.take(|a| *a == Act::Stop).await;
+        });
+        store.dispatch(Act::Ping);
+        assert_eq!(pongs(&store), 1);
+
+        parent.cancel();
+        let child = child.borrow_mut().take().unwrap();
+        assert!(parent.is_cancelled());
+        assert!(child.is_cancelled());
+        store.dispatch(Act::Ping);
+        store.dispatch(Act::Stop);
+        assert_eq!(pongs(&store), 1);
+        assert!(runtime.shared.tasks.borrow().is_empty());
+    }
+
+    #[test]
+    fn cancelled_child_leaves_parent_running() {
+        let store = store();
+        let runtime = SagaRuntime::new(&store);
+        let parent = runtime.spawn(|fx| async move {
+            let child = fx.fork(|fx| async move {
+                fx.take(|a| *a == Act::Stop).await;
+                fx.put(Act::Failed("child finished".into()));
+            });
+            child.cancel();
+            fx.take(|a| *a == Act::Ping).await;
+            fx.put(Act::Pong);
+        });
+        store.dispatch(Act::Stop);
+        store.dispatch(Act::Ping);
+        assert_eq!(store.get_state(), vec![Act::Stop, Act::Ping, Act::Pong]);
+        assert!(!parent.is_running());
+        assert!(!parent.is_cancelled());
+    }
+
+    #[test]
+    fn race_drops_the_losing_take() {
+        let store = store();
+        let runtime = SagaRuntime::new(&store);
+        let handle = runtime.spawn(|fx| async move {
+            race(fx.take(|a| *a == Act::Ping), fx.take(|a| *a == Act::Stop)).await
+        });
+        store.dispatch(Act::Stop);
+        assert!(!handle.is_running());
+        assert_eq!(
+            handle.result.borrow_mut().take(),
+            Some(Either::Right(Act::Stop))
+        );
+        // 输掉的 take 已被 drop，下一个 action 到来时顺便清理
+        store.dispatch(Act::Ping);
+        assert!(runtime.shared.takers.borrow().is_empty());
+    }
+
+    #[test]
+    fn aborted_call_puts_rejection() {
+        let store = store();
+        let runtime = SagaRuntime::new(&store);
+        let controller = AbortController::new();
+        let signal = controller.signal();
+        let handle = runtime.spawn(move |fx| async move {
+            let pending = fx.take(|a| *a == Act::Stop);
+            fx.call_abortable(&signal, pending, |aborted| Act::Failed(aborted.reason))
+                .await
+        });
+        controller.abort("timeout");
+        runtime.run_until_stalled();
+        assert_eq!(store.get_state(), vec![Act::Failed("timeout".into())]);
+        assert_eq!(handle.result.borrow_mut().take(), Some(None));
+    }
+
+    #[test]
+    fn cancel_on_signal_stops_task() {
+        let store = store();
+        let runtime = SagaRuntime::new(&store);
+        let controller = AbortController::new();
+        let handle = runtime.spawn(|fx| async move {
+            loop {
+                fx.take(|a| *a == Act::Ping).await;
+                fx.put(Act::Pong);
+            }
+        });
+        handle.cancel_on(&controller.signal());
+        controller.abort("done");
+        store.dispatch(Act::Ping);
+        assert!(handle.is_cancelled());
+        assert_eq!(pongs(&store), 0);
+    }
+}
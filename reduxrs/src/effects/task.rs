@@ -0,0 +1,143 @@
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::task::{Context, Poll, Waker};
+
+use super::abort::AbortSignal;
+
+pub type TaskId = u64;
+
+// 用 trait 做一次类型擦除，让 TaskHandle 不携带 S/A 泛型
+pub(crate) trait CancelTask {
+    fn cancel_task(&self, id: TaskId);
+}
+
+/// 任务与其句柄共享的状态
+#[derive(Default)]
+pub(crate) struct TaskState {
+    done: Cell<bool>,
+    cancelled: Cell<bool>,
+    join_wakers: RefCell<Vec<Waker>>,
+}
+
+impl TaskState {
+    pub(crate) fn finish(&self) {
+        self.done.set(true);
+        for waker in self.join_wakers.borrow_mut().drain(..) {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.cancelled.set(true);
+        self.finish();
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+}
+
+/// spawn / fork 返回的句柄：可以 join 等待结果，也可以 cancel
+/// drop 句柄不会取消任务
+pub struct TaskHandle<T> {
+    pub(crate) id: TaskId,
+    pub(crate) state: Rc<TaskState>,
+    pub(crate) result: Rc<RefCell<Option<T>>>,
+    pub(crate) runtime: Weak<dyn CancelTask>,
+}
+
+impl<T> TaskHandle<T> {
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.state.done.get()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.is_cancelled()
+    }
+
+    /// 取消任务（连同它 fork 出来的子任务）：future 被直接 drop
+    pub fn cancel(&self) {
+        if let Some(runtime) = self.runtime.upgrade() {
+            runtime.cancel_task(self.id);
+        }
+    }
+
+    /// signal 被 abort 时取消任务
+    pub fn cancel_on(&self, signal: &AbortSignal) {
+        let id = self.id;
+        let runtime = self.runtime.clone();
+        signal.on_abort(move |_| {
+            if let Some(runtime) = runtime.upgrade() {
+                runtime.cancel_task(id);
+            }
+        });
+    }
+
+    /// 等待任务结束；被取消时得到 None
+    pub fn join(&self) -> Join<T> {
+        Join {
+            state: self.state.clone(),
+            result: self.result.clone(),
+        }
+    }
+}
+
+pub struct Join<T> {
+    state: Rc<TaskState>,
+    result: Rc<RefCell<Option<T>>>,
+}
+
+impl<T> Future for Join<T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.state.done.get() {
+            return Poll::Ready(self.result.borrow_mut().take());
+        }
+        let mut wakers = self.state.join_wakers.borrow_mut();
+        // 同一个任务反复 poll 时只留一份 waker
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    use super::*;
+
+    struct NoopWake;
+
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[test]
+    fn repeated_join_polls_keep_one_waker() {
+        let state = Rc::new(TaskState::default());
+        let result = Rc::new(RefCell::new(None));
+        let mut join = Join {
+            state: state.clone(),
+            result: result.clone(),
+        };
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+        for _ in 0..100 {
+            assert!(Pin::new(&mut join).poll(&mut cx).is_pending());
+        }
+        assert_eq!(state.join_wakers.borrow().len(), 1);
+        *result.borrow_mut() = Some(7);
+        state.finish();
+        assert_eq!(Pin::new(&mut join).poll(&mut cx), Poll::Ready(Some(7)));
+    }
+}
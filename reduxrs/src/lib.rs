@@ -1 +1,15 @@
-pub mod core;
\ No newline at end of file
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod core;
+#[cfg(feature = "std")]
+pub mod effects;
+#[cfg(feature = "sync")]
+pub mod sync_remote;
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "yew")]
+pub mod yew_hooks;
\ No newline at end of file
@@ -0,0 +1,4 @@
+pub mod primary;
+pub mod protocol;
+pub mod replica;
+pub mod transport;
\ No newline at end of file
@@ -0,0 +1,282 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::protocol::{SyncError, SyncMessage, decode, encode};
+use super::transport::Transport;
+use crate::core::store::Store;
+
+type Replicas = Rc<RefCell<Vec<Box<dyn Transport>>>>;
+type WeakReplicas = Weak<RefCell<Vec<Box<dyn Transport>>>>;
+
+/// 主节点：持有权威 state，把每个提交的 action 广播给所有 replica
+/// replica 发来的 action 在这里 dispatch，再经广播回到所有 replica（包括发起者）
+/// 广播在 commit 时进行（见 Store::on_commit），auto batch 合并了通知的 action 也会逐个发送；
+/// primary 上的 restore 以 Snapshot 广播，replica 随之 restore
+pub struct Primary<S, A> {
+    store: Store<S, A>,
+    replicas: Replicas,
+}
+
+impl<S, A> Primary<S, A>
+where
+    S: Serialize + DeserializeOwned + Clone + 'static,
+    A: Serialize + DeserializeOwned + 'static,
+{
+    pub fn new(store: Store<S, A>) -> Self {
+        let replicas: Replicas = Rc::new(RefCell::new(Vec::new()));
+        // hook 无法移除，Primary 被 drop 后不再广播
+        let targets = Rc::downgrade(&replicas);
+        store.on_commit(move |_state, action, _| {
+            broadcast(&targets, encode::<&S, &A>(&SyncMessage::Action(action)));
+        });
+        let targets = Rc::downgrade(&replicas);
+        store.on_restore(move |state, _| {
+            broadcast(&targets, encode::<&S, &A>(&SyncMessage::Snapshot(state)));
+        });
+        Self { store, replicas }
+    }
+
+    pub fn store(&self) -> &Store<S, A> {
+        &self.store
+    }
+
+    pub fn add_replica(&self, transport: impl Transport + 'static) {
+        self.replicas.borrow_mut().push(Box::new(transport));
+    }
+
+    pub fn replica_count(&self) -> usize {
+        self.replicas.borrow().len()
+    }
+
+    /// 处理所有 replica 已到达的消息（不阻塞）
+    /// 断开的 replica 直接移除；违反协议的 replica 也会移除，其余 replica 的 action 照常 dispatch 之后返回错误
+    pub fn poll(&self) -> Result<(), SyncError> {
+        // 先收齐再 dispatch：dispatch 会触发广播，期间不能借用 replicas
+        let mut incoming = Vec::new();
+        let mut first_error = None;
+        self.replicas.borrow_mut().retain(|replica| {
+            match self.drain(replica.as_ref(), &mut incoming) {
+                Ok(()) => true,
+                Err(SyncError::Io(_)) => false,
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                    false
+                }
+            }
+        });
+
+        for action in incoming {
+            self.store.dispatch(action);
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    fn drain(&self, replica: &dyn Transport, incoming: &mut Vec<A>) -> Result<(), SyncError> {
+        while let Some(frame) = replica.try_recv()? {
+            match decode::<S, A>(&frame)? {
+                // 立即回复：之后广播的 action 都在快照之后
+                SyncMessage::RequestSnapshot => {
+                    let state = self.store.get_state();
+                    replica.send(encode::<S, A>(&SyncMessage::Snapshot(state))?)?;
+                }
+                SyncMessage::Action(action) => incoming.push(action),
+                SyncMessage::Snapshot(_) => {
+                    return Err(SyncError::Protocol("primary received a snapshot"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn broadcast(targets: &WeakReplicas, frame: Result<Vec<u8>, SyncError>) {
+    let (Some(targets), Ok(frame)) = (targets.upgrade(), frame) else {
+        return;
+    };
+    // 发送失败视为断开，移除这个 replica
+    targets
+        .borrow_mut()
+        .retain(|replica| replica.send(frame.clone()).is_ok());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::core::autobatch::BatchSchedule;
+    use crate::core::snapshot::StateSnapshot;
+    use crate::core::storet::AppAction;
+    use crate::sync_remote::replica::Replica;
+    use crate::sync_remote::transport::{ChannelTransport, channel_pair};
+
+    fn double_add(state: &i32, action: &i32) -> i32 {
+        state * 2 + action
+    }
+
+    // replica 一侧收到的全部 action
+    fn received(transport: &dyn Transport) -> Vec<i32> {
+        let mut actions = Vec::new();
+        while let Some(frame) = transport.try_recv().unwrap() {
+            if let SyncMessage::Action(action) = decode::<i32, i32>(&frame).unwrap() {
+                actions.push(action);
+            }
+        }
+        actions
+    }
+
+    #[test]
+    fn broadcasts_actions_whose_notification_was_batched() {
+        let store = Store::new(double_add, 0);
+        store.enable_auto_batch(|action: &i32| *action == 1, BatchSchedule::Manual);
+        let primary = Primary::new(store);
+        let (local, remote) = channel_pair();
+        primary.add_replica(local);
+        primary.store().dispatch(1);
+        primary.store().dispatch(1);
+        primary.store().flush_batched();
+        primary.store().dispatch(5);
+        let actions = received(&remote);
+        assert_eq!(actions, vec![1, 1, 5]);
+        assert_eq!(
+            actions
+                .iter()
+                .fold(0, |state, action| double_add(&state, action)),
+            11
+        );
+        assert_eq!(primary.store().get_state(), 11);
+    }
+
+    fn add(state: &i32, action: &i32) -> i32 {
+        state + action
+    }
+
+    fn send(transport: &ChannelTransport, message: SyncMessage<i32, i32>) {
+        transport.send(encode(&message).unwrap()).unwrap();
+    }
+
+    type Op = AppAction<i32>;
+
+    // 一直 poll 到 done 为 true；replica 线程提前退出（例如 panic）或超时时失败，而不是卡住
+    fn poll_until(
+        primary: &Primary<i32, Op>,
+        replica: &JoinHandle<i32>,
+        mut done: impl FnMut() -> bool,
+    ) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            primary.poll().unwrap();
+            if done() {
+                return;
+            }
+            assert!(!replica.is_finished(), "Replica thread exited early.");
+            assert!(
+                Instant::now() < deadline,
+                "Timed out waiting for the replica."
+            );
+            thread::yield_now();
+        }
+    }
+
+    fn add_op(state: &i32, action: &Op) -> i32 {
+        match action {
+            AppAction::Business(n) => state + n,
+            AppAction::Internal(_) => *state,
+        }
+    }
+
+    #[test]
+    fn replica_receives_snapshot_then_round_trips_actions() {
+        let primary = Primary::new(Store::new(add_op, 10));
+        let (local, remote) = channel_pair();
+        primary.add_replica(local);
+        // 连接之前的 action 已经包含在快照里
+        primary.store().dispatch(AppAction::Business(1));
+
+        let (snapshot_tx, snapshot_rx) = mpsc::channel();
+        let (resume_tx, resume_rx) = mpsc::channel::<()>();
+        let replica = thread::spawn(move || {
+            let replica = Replica::connect(remote, add_op, |_| panic!("forward failed")).unwrap();
+            snapshot_tx.send(replica.store().get_state()).unwrap();
+            replica.store().dispatch(AppAction::Business(5));
+            // 本地 dispatch 只转发，不在本地生效
+            assert_eq!(replica.store().get_state(), 11);
+            resume_rx.recv().unwrap();
+            replica.poll().unwrap();
+            replica.store().get_state()
+        });
+
+        let mut snapshot = None;
+        poll_until(&primary, &replica, || {
+            snapshot = snapshot_rx.try_recv().ok();
+            snapshot.is_some()
+        });
+        assert_eq!(snapshot, Some(11));
+        poll_until(&primary, &replica, || primary.store().get_state() != 11);
+        resume_tx.send(()).unwrap();
+        assert_eq!(replica.join().unwrap(), 16);
+        assert_eq!(primary.store().get_state(), 16);
+    }
+
+    #[test]
+    fn disconnected_replica_is_removed_without_losing_others() {
+        let primary = Primary::new(Store::new(add, 0));
+        let (healthy, healthy_remote) = channel_pair();
+        let (dead, dead_remote) = channel_pair();
+        primary.add_replica(dead);
+        primary.add_replica(healthy);
+        drop(dead_remote);
+        send(&healthy_remote, SyncMessage::Action(2));
+
+        primary.poll().unwrap();
+        assert_eq!(primary.store().get_state(), 2);
+        assert_eq!(primary.replica_count(), 1);
+        send(&healthy_remote, SyncMessage::Action(3));
+        primary.poll().unwrap();
+        assert_eq!(primary.store().get_state(), 5);
+        assert_eq!(received(&healthy_remote), vec![2, 3]);
+    }
+
+    #[test]
+    fn misbehaving_replica_is_removed_and_reported() {
+        let primary = Primary::new(Store::new(add, 0));
+        let (bad, bad_remote) = channel_pair();
+        let (good, good_remote) = channel_pair();
+        primary.add_replica(bad);
+        primary.add_replica(good);
+        send(&bad_remote, SyncMessage::Snapshot(99));
+        send(&good_remote, SyncMessage::Action(4));
+
+        assert!(matches!(primary.poll(), Err(SyncError::Protocol(_))));
+        assert_eq!(primary.store().get_state(), 4);
+        assert_eq!(primary.replica_count(), 1);
+        primary.poll().unwrap();
+    }
+
+    #[test]
+    fn restore_reaches_replicas() {
+        let primary = Primary::new(Store::new(add_op, 0));
+        let (local, remote) = channel_pair();
+        primary.add_replica(local);
+        let (resume_tx, resume_rx) = mpsc::channel::<()>();
+        let replica = thread::spawn(move || {
+            let replica = Replica::connect(remote, add_op, |_| panic!("forward failed")).unwrap();
+            replica.store().dispatch(AppAction::Business(1));
+            resume_rx.recv().unwrap();
+            replica.poll().unwrap();
+            replica.store().get_state()
+        });
+
+        poll_until(&primary, &replica, || primary.store().get_state() != 0);
+        primary.store().restore(StateSnapshot::new(40));
+        primary.store().dispatch(AppAction::Business(2));
+        resume_tx.send(()).unwrap();
+        assert_eq!(replica.join().unwrap(), 42);
+        assert_eq!(primary.store().get_state(), 42);
+    }
+}
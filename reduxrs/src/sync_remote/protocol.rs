@@ -0,0 +1,64 @@
+use std::fmt;
+use std::io;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// 线上的消息：replica 连上后先要一次全量快照，之后只传 action
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SyncMessage<S, A> {
+    RequestSnapshot,
+    Snapshot(S),
+    Action(A),
+}
+
+#[derive(Debug)]
+pub enum SyncError {
+    Io(io::Error),
+    Codec(serde_json::Error),
+    /// 对端发来了当前角色不该收到的消息
+    Protocol(&'static str),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncError::Io(e) => write!(f, "sync transport error: {}", e),
+            SyncError::Codec(e) => write!(f, "sync codec error: {}", e),
+            SyncError::Protocol(msg) => write!(f, "sync protocol error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+impl From<io::Error> for SyncError {
+    fn from(e: io::Error) -> Self {
+        // 传输层把协议错误（例如超长帧）包在 io::Error 里，这里还原
+        match e
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<SyncError>())
+        {
+            Some(SyncError::Protocol(msg)) => SyncError::Protocol(msg),
+            _ => SyncError::Io(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for SyncError {
+    fn from(e: serde_json::Error) -> Self {
+        SyncError::Codec(e)
+    }
+}
+
+pub(crate) fn encode<S: Serialize, A: Serialize>(
+    message: &SyncMessage<S, A>,
+) -> Result<Vec<u8>, SyncError> {
+    Ok(serde_json::to_vec(message)?)
+}
+
+pub(crate) fn decode<S: DeserializeOwned, A: DeserializeOwned>(
+    frame: &[u8],
+) -> Result<SyncMessage<S, A>, SyncError> {
+    Ok(serde_json::from_slice(frame)?)
+}
@@ -0,0 +1,216 @@
+use std::rc::Rc;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::protocol::{SyncError, SyncMessage, decode, encode};
+use super::transport::Transport;
+use crate::core::middleware::{MiddlewareApi, Next};
+use crate::core::snapshot::StateSnapshot;
+use crate::core::store::{DispatchMark, Store};
+use crate::core::storet::InternalAction;
+
+/// 本地 dispatch 的 action 没能转发给 primary（也没有在本地生效），交回调用方重试或上报
+#[derive(Debug)]
+pub struct ForwardError<A> {
+    pub action: A,
+    pub error: SyncError,
+}
+
+/// 副本：state 只由 primary 广播的 action 推进
+/// 本地 store.dispatch 的 action 会被转发给 primary，而不是直接在本地生效；
+/// primary restore 之后广播的 Snapshot 在本地 restore（listeners 收到 RESTORE），所以 A 需要 From<InternalAction>
+pub struct Replica<S, A> {
+    store: Store<S, A>,
+    transport: Rc<dyn Transport>,
+    remote: DispatchMark,
+}
+
+impl<S, A> Replica<S, A>
+where
+    S: Serialize + DeserializeOwned + Clone + 'static,
+    A: Serialize + DeserializeOwned + From<InternalAction> + 'static,
+{
+    /// 请求全量快照并阻塞等待，用快照作为初始 state 创建本地 store
+    /// 本地 action 转发失败时调用 on_forward_error
+    pub fn connect(
+        transport: impl Transport + 'static,
+        reducer: impl Fn(&S, &A) -> S + 'static,
+        on_forward_error: impl Fn(ForwardError<A>) + 'static,
+    ) -> Result<Self, SyncError> {
+        transport.send(encode::<S, A>(&SyncMessage::RequestSnapshot)?)?;
+        // 快照之前到达的 action 已经包含在快照里，丢弃即可
+        let snapshot = loop {
+            match decode::<S, A>(&transport.recv()?)? {
+                SyncMessage::Snapshot(state) => break state,
+                SyncMessage::Action(_) => continue,
+                SyncMessage::RequestSnapshot => {
+                    return Err(SyncError::Protocol("replica received a snapshot request"));
+                }
+            }
+        };
+
+        let transport: Rc<dyn Transport> = Rc::new(transport);
+        let remote = DispatchMark::new();
+        let store = Store::new(reducer, snapshot);
+
+        let forward_to = transport.clone();
+        let is_remote = remote.clone();
+        store.apply_middleware(
+            move |_api: &dyn MiddlewareApi<S, A>, action: A, next: Next<A>| {
+                // 按 action 标记：poll 在 dispatch 期间（例如 listener 里）排队的远端 action
+                // 与本地 action 交错时也不会认错
+                if is_remote.take() {
+                    next(action);
+                    return;
+                }
+                let sent = encode::<&S, &A>(&SyncMessage::Action(&action))
+                    .and_then(|frame| Ok(forward_to.send(frame)?));
+                if let Err(error) = sent {
+                    on_forward_error(ForwardError { action, error });
+                }
+            },
+        );
+
+        Ok(Self {
+            store,
+            transport,
+            remote,
+        })
+    }
+
+    pub fn store(&self) -> &Store<S, A> {
+        &self.store
+    }
+
+    /// 应用 primary 已到达的 action 和 Snapshot（不阻塞）
+    pub fn poll(&self) -> Result<(), SyncError> {
+        while let Some(frame) = self.transport.try_recv()? {
+            match decode::<S, A>(&frame)? {
+                SyncMessage::Action(action) => self.store.dispatch_marked(action, &self.remote),
+                SyncMessage::Snapshot(state) => self.store.restore(StateSnapshot::new(state)),
+                SyncMessage::RequestSnapshot => {
+                    return Err(SyncError::Protocol("replica received a snapshot request"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::io::ErrorKind;
+
+    use super::*;
+    use crate::core::storet::AppAction;
+    use crate::sync_remote::transport::{ChannelTransport, channel_pair};
+
+    type Op = AppAction<i32>;
+
+    fn add(state: &i32, action: &Op) -> i32 {
+        match action {
+            AppAction::Business(n) => state + n,
+            AppAction::Internal(_) => *state,
+        }
+    }
+
+    fn send(transport: &ChannelTransport, message: SyncMessage<i32, Op>) {
+        transport.send(encode(&message).unwrap()).unwrap();
+    }
+
+    fn connect(transport: ChannelTransport) -> Replica<i32, Op> {
+        Replica::connect(transport, add, |_| panic!("forward failed")).unwrap()
+    }
+
+    #[test]
+    fn actions_before_snapshot_are_skipped() {
+        let (primary, remote) = channel_pair();
+        send(&primary, SyncMessage::Action(AppAction::Business(99)));
+        send(&primary, SyncMessage::Snapshot(5));
+        send(&primary, SyncMessage::Action(AppAction::Business(1)));
+        let replica = connect(remote);
+        assert!(matches!(
+            decode::<i32, Op>(&primary.recv().unwrap()).unwrap(),
+            SyncMessage::RequestSnapshot
+        ));
+        assert_eq!(replica.store().get_state(), 5);
+        replica.poll().unwrap();
+        assert_eq!(replica.store().get_state(), 6);
+    }
+
+    #[test]
+    fn snapshot_from_primary_restores_state() {
+        let (primary, remote) = channel_pair();
+        send(&primary, SyncMessage::Snapshot(0));
+        let replica = connect(remote);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let _sub = replica.store().subscribe(move |state, action| {
+            log.borrow_mut()
+                .push((*state, matches!(action, AppAction::Internal(_))));
+        });
+
+        send(&primary, SyncMessage::Action(AppAction::Business(1)));
+        send(&primary, SyncMessage::Snapshot(40));
+        send(&primary, SyncMessage::Action(AppAction::Business(2)));
+        replica.poll().unwrap();
+        assert_eq!(replica.store().get_state(), 42);
+        assert_eq!(*seen.borrow(), [(1, false), (40, true), (42, false)]);
+    }
+
+    #[test]
+    fn failed_forward_is_reported_with_the_action() {
+        let (primary, remote) = channel_pair();
+        send(&primary, SyncMessage::Snapshot(5));
+        let failures = Rc::new(RefCell::new(Vec::new()));
+        let sink = failures.clone();
+        let replica = Replica::connect(remote, add, move |failure: ForwardError<Op>| {
+            sink.borrow_mut().push(failure);
+        })
+        .unwrap();
+        drop(primary);
+
+        replica.store().dispatch(AppAction::Business(3));
+        assert_eq!(replica.store().get_state(), 5);
+        let failures = failures.borrow();
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(failures[0].action, AppAction::Business(3)));
+        assert!(
+            matches!(&failures[0].error, SyncError::Io(e) if e.kind() == ErrorKind::BrokenPipe)
+        );
+    }
+
+    #[test]
+    fn poll_from_listener_keeps_remote_and_local_actions_apart() {
+        let (primary, remote) = channel_pair();
+        send(&primary, SyncMessage::Snapshot(0));
+        send(&primary, SyncMessage::Action(AppAction::Business(1)));
+        let replica = Rc::new(connect(remote));
+        primary.recv().unwrap();
+        let primary = Rc::new(primary);
+
+        // 收到第一个远端 action 时：先本地 dispatch，再 poll 出排在它后面的远端 action
+        let (handle, upstream) = (Rc::downgrade(&replica), primary.clone());
+        let _sub = replica.store().subscribe(move |state, _| {
+            let Some(replica) = handle.upgrade() else {
+                return;
+            };
+            if *state == 1 {
+                send(&upstream, SyncMessage::Action(AppAction::Business(10)));
+                replica.store().dispatch(AppAction::Business(100));
+                replica.poll().unwrap();
+            }
+        });
+        replica.poll().unwrap();
+        assert_eq!(replica.store().get_state(), 11);
+        // 只有本地 action 被转发，远端 action 没有被送回 primary
+        let forwarded = decode::<i32, Op>(&primary.recv().unwrap()).unwrap();
+        assert!(matches!(
+            forwarded,
+            SyncMessage::Action(AppAction::Business(100))
+        ));
+        assert_eq!(primary.try_recv().unwrap(), None);
+    }
+}
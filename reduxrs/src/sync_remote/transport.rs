@@ -0,0 +1,238 @@
+use std::cell::RefCell;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+
+use super::protocol::SyncError;
+
+/// 传输层：收发完整的帧（一帧 = 一条编码后的 SyncMessage）
+/// websocket / IPC 等实现这个 trait 即可接入
+pub trait Transport {
+    fn send(&self, frame: Vec<u8>) -> io::Result<()>;
+    /// 阻塞直到收到一帧
+    fn recv(&self) -> io::Result<Vec<u8>>;
+    /// 不阻塞：暂时没有数据时返回 Ok(None)
+    fn try_recv(&self) -> io::Result<Option<Vec<u8>>>;
+}
+
+/// 进程内的传输（mpsc），用于多线程 / 测试
+pub struct ChannelTransport {
+    sender: Sender<Vec<u8>>,
+    receiver: Receiver<Vec<u8>>,
+}
+
+/// 一对互相连接的 ChannelTransport
+pub fn channel_pair() -> (ChannelTransport, ChannelTransport) {
+    let (a_tx, a_rx) = mpsc::channel();
+    let (b_tx, b_rx) = mpsc::channel();
+    (
+        ChannelTransport {
+            sender: a_tx,
+            receiver: b_rx,
+        },
+        ChannelTransport {
+            sender: b_tx,
+            receiver: a_rx,
+        },
+    )
+}
+
+fn disconnected() -> io::Error {
+    io::Error::new(ErrorKind::BrokenPipe, "peer disconnected")
+}
+
+impl Transport for ChannelTransport {
+    fn send(&self, frame: Vec<u8>) -> io::Result<()> {
+        self.sender.send(frame).map_err(|_| disconnected())
+    }
+
+    fn recv(&self) -> io::Result<Vec<u8>> {
+        self.receiver.recv().map_err(|_| disconnected())
+    }
+
+    fn try_recv(&self) -> io::Result<Option<Vec<u8>>> {
+        match self.receiver.try_recv() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(disconnected()),
+        }
+    }
+}
+
+/// TcpTransport 默认接受的最大帧长度
+pub const DEFAULT_MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+/// TCP 传输：每帧前面加 4 字节大端长度
+pub struct TcpTransport {
+    stream: TcpStream,
+    // try_recv 可能只读到半帧，先攒着
+    buffer: RefCell<Vec<u8>>,
+    max_frame_len: usize,
+}
+
+impl TcpTransport {
+    pub fn new(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            buffer: RefCell::new(Vec::new()),
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+        }
+    }
+
+    /// 收到长度超过 limit 的帧头时不再缓冲，直接返回错误
+    /// （转换成 SyncError 后为 SyncError::Protocol；之后这个连接不再可用）
+    pub fn with_max_frame_len(mut self, limit: usize) -> Self {
+        self.max_frame_len = limit;
+        self
+    }
+
+    fn take_frame(&self) -> io::Result<Option<Vec<u8>>> {
+        let mut buffer = self.buffer.borrow_mut();
+        if buffer.len() < 4 {
+            return Ok(None);
+        }
+        let len = u32::from_be_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize;
+        if len > self.max_frame_len {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                SyncError::Protocol("frame exceeds the maximum length"),
+            ));
+        }
+        if buffer.len() < 4 + len {
+            return Ok(None);
+        }
+        let frame = buffer[4..4 + len].to_vec();
+        buffer.drain(..4 + len);
+        Ok(Some(frame))
+    }
+
+    fn fill(&self) -> io::Result<usize> {
+        let mut chunk = [0u8; 4096];
+        let read = (&self.stream).read(&mut chunk)?;
+        if read == 0 {
+            return Err(disconnected());
+        }
+        self.buffer.borrow_mut().extend_from_slice(&chunk[..read]);
+        Ok(read)
+    }
+}
+
+impl Transport for TcpTransport {
+    fn send(&self, frame: Vec<u8>) -> io::Result<()> {
+        let len = u32::try_from(frame.len())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "frame too large"))?;
+        let mut stream = &self.stream;
+        stream.write_all(&len.to_be_bytes())?;
+        stream.write_all(&frame)?;
+        stream.flush()
+    }
+
+    fn recv(&self) -> io::Result<Vec<u8>> {
+        loop {
+            if let Some(frame) = self.take_frame()? {
+                return Ok(frame);
+            }
+            self.fill()?;
+        }
+    }
+
+    fn try_recv(&self) -> io::Result<Option<Vec<u8>>> {
+        if let Some(frame) = self.take_frame()? {
+            return Ok(Some(frame));
+        }
+        self.stream.set_nonblocking(true)?;
+        let result = loop {
+            match self.fill() {
+                Ok(_) => continue,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break Ok(()),
+                Err(e) => break Err(e),
+            }
+        };
+        self.stream.set_nonblocking(false)?;
+        result.and_then(|_| self.take_frame())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    use super::*;
+
+    // (client, server, 绕过 framing 直接写 server 的原始流)
+    fn tcp_pair() -> (TcpTransport, TcpTransport, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        let raw = client.try_clone().unwrap();
+        (TcpTransport::new(client), TcpTransport::new(server), raw)
+    }
+
+    #[test]
+    fn tcp_frames_keep_their_boundaries() {
+        let (client, server, _) = tcp_pair();
+        let large = vec![7u8; 10_000];
+        client.send(b"first".to_vec()).unwrap();
+        client.send(Vec::new()).unwrap();
+        client.send(large.clone()).unwrap();
+        assert_eq!(server.recv().unwrap(), b"first");
+        assert_eq!(server.recv().unwrap(), b"");
+        assert_eq!(server.recv().unwrap(), large);
+    }
+
+    #[test]
+    fn tcp_try_recv_buffers_partial_frames() {
+        let (_client, server, mut raw) = tcp_pair();
+        assert_eq!(server.try_recv().unwrap(), None);
+        raw.write_all(&[0, 0, 0, 3, b'x']).unwrap();
+        // 阻塞读到半帧为止（超时保护）
+        server
+            .stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        while server.buffer.borrow().len() < 5 {
+            server.fill().unwrap();
+        }
+        // 半帧：先攒着，不返回
+        assert_eq!(server.try_recv().unwrap(), None);
+        raw.write_all(b"yz").unwrap();
+        assert_eq!(server.recv().unwrap(), b"xyz");
+    }
+
+    #[test]
+    fn tcp_rejects_frames_above_limit() {
+        let (client, server, mut raw) = tcp_pair();
+        let server = server.with_max_frame_len(4);
+        client.send(b"four".to_vec()).unwrap();
+        assert_eq!(server.recv().unwrap(), b"four");
+        // 只有帧头：不等 payload 到达就拒绝
+        raw.write_all(&u32::MAX.to_be_bytes()).unwrap();
+        let error = SyncError::from(server.recv().unwrap_err());
+        assert!(matches!(error, SyncError::Protocol(_)));
+        assert!(matches!(
+            server.try_recv().map_err(SyncError::from),
+            Err(SyncError::Protocol(_))
+        ));
+    }
+
+    #[test]
+    fn tcp_reports_disconnect() {
+        let (client, server, raw) = tcp_pair();
+        drop(client);
+        drop(raw);
+        assert_eq!(server.recv().unwrap_err().kind(), ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn channel_reports_disconnect() {
+        let (a, b) = channel_pair();
+        assert_eq!(a.try_recv().unwrap(), None);
+        drop(b);
+        assert_eq!(a.try_recv().unwrap_err().kind(), ErrorKind::BrokenPipe);
+        assert_eq!(
+            a.send(Vec::new()).unwrap_err().kind(),
+            ErrorKind::BrokenPipe
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod mock;
+pub mod recording;
+#[cfg(feature = "proptest")]
+pub mod reducer_laws;
\ No newline at end of file
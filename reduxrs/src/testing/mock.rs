@@ -0,0 +1,212 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::rc::{Rc, Weak};
+
+use crate::core::middleware::{Chain, Middleware, MiddlewareApi, run_chain};
+use crate::core::store::{AnyUnsubscribe, Listener, ListenerId, ListenerKey, Subscription};
+use crate::core::store_api::StoreApi;
+
+/// 等价 redux-mock-store：不跑 reducer，只记录到达链尾的 action，get_state 返回预设值
+/// 用来单独测试 middleware / thunk 的副作用
+pub struct MockStore<S, A> {
+    inner: Rc<MockInner<S, A>>,
+}
+
+impl<S, A> Clone for MockStore<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+struct MockInner<S, A> {
+    state: RefCell<S>,
+    actions: RefCell<Vec<A>>,
+    middleware: RefCell<Chain<S, A>>,
+    listeners: Rc<RefCell<MockListeners<S, A>>>,
+}
+
+type MockListener<S, A> = Rc<RefCell<Box<Listener<S, A>>>>;
+
+struct MockListeners<S, A> {
+    entries: BTreeMap<ListenerKey, MockListener<S, A>>,
+    next_id: ListenerId,
+}
+
+impl<S, A> AnyUnsubscribe for MockListeners<S, A> {
+    fn unsubscribe_by_key(&mut self, key: ListenerKey) {
+        self.entries.remove(&key);
+    }
+}
+
+impl<S: Clone + 'static, A: 'static> MockStore<S, A> {
+    pub fn new(state: S) -> Self {
+        Self {
+            inner: Rc::new(MockInner {
+                state: RefCell::new(state),
+                actions: RefCell::new(Vec::new()),
+                middleware: RefCell::new(Rc::new(Vec::new())),
+                listeners: Rc::new(RefCell::new(MockListeners {
+                    entries: BTreeMap::new(),
+                    next_id: 0,
+                })),
+            }),
+        }
+    }
+
+    /// 改变之后 get_state 的返回值（模拟 state 在别处被更新）
+    pub fn set_state(&self, state: S) {
+        *self.inner.state.borrow_mut() = state;
+    }
+
+    pub fn get_state(&self) -> S {
+        self.inner.state.borrow().clone()
+    }
+
+    /// 被测的 middleware；多次调用时先 apply 的在前
+    pub fn apply_middleware(&self, middleware: impl Middleware<S, A> + 'static) {
+        Rc::make_mut(&mut self.inner.middleware.borrow_mut()).push(Rc::new(middleware));
+    }
+
+    /// 经过已 apply 的 middleware，最后只记录，不跑 reducer
+    /// 到达链尾时先用预设 state 通知 listener，再记录 action
+    pub fn dispatch(&self, action: A) {
+        let chain = self.inner.middleware.borrow().clone();
+        let inner = self.inner.clone();
+        run_chain(
+            Rc::new(self.clone()),
+            chain,
+            action,
+            Rc::new(move |action| {
+                let state = inner.state.borrow().clone();
+                let listeners: Vec<_> =
+                    inner.listeners.borrow().entries.values().cloned().collect();
+                for listener in listeners {
+                    (listener.borrow_mut())(&state, &action);
+                }
+                inner.actions.borrow_mut().push(action);
+            }),
+        );
+    }
+
+    /// 每个到达链尾的 action 都会通知（state 不变，就是预设值）
+    pub fn subscribe(&self, listener: impl FnMut(&S, &A) + 'static) -> Subscription {
+        let key = {
+            let mut listeners = self.inner.listeners.borrow_mut();
+            let key = (0, listeners.next_id);
+            listeners.next_id += 1;
+            listeners
+                .entries
+                .insert(key, Rc::new(RefCell::new(Box::new(listener))));
+            key
+        };
+        let erased: Rc<RefCell<dyn AnyUnsubscribe>> = self.inner.listeners.clone();
+        let weak: Weak<RefCell<dyn AnyUnsubscribe>> = Rc::downgrade(&erased);
+        Subscription::new(weak, key)
+    }
+
+    pub fn actions(&self) -> Vec<A>
+    where
+        A: Clone,
+    {
+        self.inner.actions.borrow().clone()
+    }
+
+    pub fn clear_actions(&self) {
+        self.inner.actions.borrow_mut().clear();
+    }
+
+    /// 断言到达链尾的 action 序列（按顺序、完全相等）
+    pub fn expect_actions(&self, expected: &[A])
+    where
+        A: PartialEq + Debug,
+    {
+        assert_eq!(
+            self.inner.actions.borrow().as_slice(),
+            expected,
+            "Dispatched actions do not match."
+        );
+    }
+}
+
+impl<S: Clone + 'static, A: 'static> MiddlewareApi<S, A> for MockStore<S, A> {
+    fn get_state(&self) -> S {
+        MockStore::get_state(self)
+    }
+
+    fn dispatch(&self, action: A) {
+        MockStore::dispatch(self, action)
+    }
+}
+
+impl<S: Clone + 'static, A: 'static> StoreApi<S, A> for MockStore<S, A> {
+    fn get_state(&self) -> S {
+        MockStore::get_state(self)
+    }
+
+    fn dispatch(&self, action: A) {
+        MockStore::dispatch(self, action)
+    }
+
+    fn subscribe(&self, listener: Box<Listener<S, A>>) -> Subscription {
+        MockStore::subscribe(self, listener)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+    use std::string::String;
+
+    use super::*;
+    use crate::core::middleware::Next;
+
+    #[test]
+    fn expect_actions_matches_actions_after_middleware() {
+        let store = MockStore::new(0);
+        // 把 0 吞掉，其余原样放行
+        store.apply_middleware(
+            |_: &dyn MiddlewareApi<i32, i32>, action: i32, next: Next<i32>| {
+                if action != 0 {
+                    next(action);
+                }
+            },
+        );
+        store.dispatch(1);
+        store.dispatch(0);
+        store.dispatch(2);
+        store.expect_actions(&[1, 2]);
+        store.clear_actions();
+        store.expect_actions(&[]);
+    }
+
+    #[test]
+    fn expect_actions_reports_mismatch() {
+        let store = MockStore::new(0);
+        store.dispatch(1);
+        let payload = catch_unwind(AssertUnwindSafe(|| store.expect_actions(&[2]))).unwrap_err();
+        let message = payload.downcast_ref::<String>().unwrap();
+        assert!(message.contains("Dispatched actions do not match."));
+        assert!(message.contains("[1]") && message.contains("[2]"));
+    }
+
+    #[test]
+    fn listeners_see_preset_state() {
+        let store = MockStore::new(10);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let sink = seen.clone();
+        let sub = store.subscribe(move |state: &i32, action: &i32| {
+            sink.borrow_mut().push((*state, *action));
+        });
+        store.dispatch(1);
+        store.set_state(20);
+        assert_eq!(store.get_state(), 20);
+        store.dispatch(2);
+        sub.unsubscribe();
+        store.dispatch(3);
+        assert_eq!(*seen.borrow(), [(10, 1), (20, 2)]);
+        store.expect_actions(&[1, 2, 3]);
+    }
+}
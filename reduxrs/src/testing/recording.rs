@@ -0,0 +1,232 @@
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use crate::core::invariant::Fingerprint;
+use crate::core::store::Store;
+
+/// 录制的事件
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordedEvent<S, A> {
+    /// 真正到达 reducer 的 action
+    Action(A),
+    /// Store::restore 直接换上的 state（不经过 reducer，回放时原样替换）
+    Restore(S),
+}
+
+/// 一条录制记录：提交的 action 或 restore，以及（可选）之后的 state 指纹
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedAction<S, A> {
+    pub event: RecordedEvent<S, A>,
+    pub state_hash: Option<u64>,
+}
+
+/// 包一层 store，录下每个提交的 action 和 restore，用于回放 / 复现 bug
+/// 录制发生在 middleware 之后（见 Store::on_commit / on_restore），所以回放只需要 reducer；
+/// auto batch 合并了通知的 action 也都会录下；RecordingStore 被 drop 后停止录制
+pub struct RecordingStore<S, A> {
+    store: Store<S, A>,
+    log: Rc<RefCell<Vec<RecordedAction<S, A>>>>,
+}
+
+impl<S: Clone + 'static, A: Clone + 'static> RecordingStore<S, A> {
+    /// 只录 action
+    pub fn new(store: Store<S, A>) -> Self {
+        Self::record(store, None)
+    }
+
+    /// 同时录下每一步的 state 指纹（见 `core::invariant::debug_fingerprint`）
+    pub fn with_state_hashes(
+        store: Store<S, A>,
+        fingerprint: impl Fn(&S) -> u64 + 'static,
+    ) -> Self {
+        Self::record(store, Some(Box::new(fingerprint)))
+    }
+
+    fn record(store: Store<S, A>, fingerprint: Option<Box<Fingerprint<S>>>) -> Self {
+        let log: Rc<RefCell<Vec<RecordedAction<S, A>>>> = Rc::new(RefCell::new(Vec::new()));
+        // hook 无法移除，只持有 Weak
+        let sink = Rc::downgrade(&log);
+        let push = Rc::new(move |event: RecordedEvent<S, A>, state: &S| {
+            if let Some(sink) = sink.upgrade() {
+                sink.borrow_mut().push(RecordedAction {
+                    event,
+                    state_hash: fingerprint.as_ref().map(|f| f(state)),
+                });
+            }
+        });
+        let on_commit = push.clone();
+        store.on_commit(move |state, action, _| {
+            on_commit(RecordedEvent::Action(action.clone()), state);
+        });
+        store.on_restore(move |state, _| {
+            push(RecordedEvent::Restore(state.clone()), state);
+        });
+        Self { store, log }
+    }
+
+    pub fn store(&self) -> &Store<S, A> {
+        &self.store
+    }
+
+    pub fn dispatch(&self, action: A)
+    where
+        S: Clone,
+    {
+        self.store.dispatch(action);
+    }
+
+    pub fn get_state(&self) -> S
+    where
+        S: Clone,
+    {
+        self.store.get_state()
+    }
+
+    /// 已提交 action 与 restore 的拷贝（按提交顺序），交给 replay 回放
+    pub fn events(&self) -> Vec<RecordedEvent<S, A>> {
+        self.log.borrow().iter().map(|r| r.event.clone()).collect()
+    }
+
+    pub fn recorded(&self) -> Vec<RecordedAction<S, A>> {
+        self.log.borrow().clone()
+    }
+
+    pub fn clear(&self) {
+        self.log.borrow_mut().clear();
+    }
+}
+
+/// 只用 reducer 重跑一组事件，遇到 Restore 直接换成录下的 state，返回最终 state
+pub fn replay<S, A>(
+    events: impl IntoIterator<Item = RecordedEvent<S, A>>,
+    reducer: impl Fn(&S, &A) -> S,
+    initial: S,
+) -> S {
+    events
+        .into_iter()
+        .fold(initial, |state, event| step(state, event, &reducer))
+}
+
+fn step<S, A>(state: S, event: RecordedEvent<S, A>, reducer: impl Fn(&S, &A) -> S) -> S {
+    match event {
+        RecordedEvent::Action(action) => reducer(&state, &action),
+        RecordedEvent::Restore(restored) => restored,
+    }
+}
+
+/// 回放并断言最终 state（golden-file 回归测试）
+pub fn assert_replay<S: PartialEq + Debug, A>(
+    events: impl IntoIterator<Item = RecordedEvent<S, A>>,
+    reducer: impl Fn(&S, &A) -> S,
+    initial: S,
+    expected: &S,
+) {
+    let actual = replay(events, reducer, initial);
+    assert_eq!(
+        &actual, expected,
+        "Replayed state does not match the expected state."
+    );
+}
+
+/// 回放一段带指纹的录制，逐步比对；定位到第一个分叉的事件
+pub fn assert_recording<S: Clone + Debug, A: Clone + Debug>(
+    recorded: &[RecordedAction<S, A>],
+    reducer: impl Fn(&S, &A) -> S,
+    initial: S,
+    fingerprint: impl Fn(&S) -> u64,
+) -> S {
+    let mut state = initial;
+    for (index, recorded) in recorded.iter().enumerate() {
+        state = step(state, recorded.event.clone(), &reducer);
+        if let Some(expected) = recorded.state_hash {
+            assert_eq!(
+                fingerprint(&state),
+                expected,
+                "Replay diverged at event #{} ({:?}).",
+                index,
+                recorded.event
+            );
+        }
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::autobatch::BatchSchedule;
+    use crate::core::snapshot::StateSnapshot;
+    use crate::core::storet::AppAction;
+
+    fn double_add(state: &i32, action: &i32) -> i32 {
+        state * 2 + action
+    }
+
+    #[test]
+    fn records_actions_whose_notification_was_batched() {
+        let store = Store::new(double_add, 0);
+        store.enable_auto_batch(|action: &i32| *action == 1, BatchSchedule::Manual);
+        let recording = RecordingStore::new(store);
+        recording.dispatch(1);
+        recording.dispatch(1);
+        recording.store().flush_batched();
+        recording.dispatch(5);
+        assert_eq!(recording.get_state(), 11);
+        assert_eq!(
+            recording.events(),
+            vec![
+                RecordedEvent::Action(1),
+                RecordedEvent::Action(1),
+                RecordedEvent::Action(5)
+            ]
+        );
+        assert_replay(recording.events(), double_add, 0, &11);
+    }
+
+    #[test]
+    fn state_hashes_follow_each_commit() {
+        let recording =
+            RecordingStore::with_state_hashes(Store::new(double_add, 0), |state| *state as u64);
+        recording.dispatch(1);
+        recording.dispatch(2);
+        let recorded = recording.recorded();
+        assert_eq!(recorded[1].state_hash, Some(4));
+        assert_eq!(
+            assert_recording(&recorded, double_add, 0, |state| *state as u64),
+            4
+        );
+    }
+
+    fn add(state: &i32, action: &AppAction<i32>) -> i32 {
+        match action {
+            AppAction::Business(n) => state + n,
+            AppAction::Internal(_) => *state,
+        }
+    }
+
+    #[test]
+    fn replay_honors_restores() {
+        let recording =
+            RecordingStore::with_state_hashes(Store::new(add, 0), |state| *state as u64);
+        recording.dispatch(AppAction::Business(1));
+        recording.store().restore(StateSnapshot::new(10));
+        recording.dispatch(AppAction::Business(1));
+        assert_eq!(recording.get_state(), 11);
+        assert_replay(recording.events(), add, 0, &11);
+        assert_eq!(
+            assert_recording(&recording.recorded(), add, 0, |state| *state as u64),
+            11
+        );
+    }
+
+    #[test]
+    fn stops_recording_when_dropped() {
+        let store = Store::new(double_add, 0);
+        let recording = RecordingStore::new(store.clone());
+        recording.dispatch(1);
+        drop(recording);
+        store.dispatch(1);
+        assert_eq!(store.get_state(), 3);
+    }
+}
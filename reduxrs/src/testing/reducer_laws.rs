@@ -0,0 +1,157 @@
+use std::fmt::Debug;
+
+use proptest::collection::vec;
+use proptest::strategy::Strategy;
+use proptest::test_runner::{TestCaseError, TestRunner};
+
+/// 默认生成的 action 序列最大长度
+pub const MAX_SEQUENCE_LEN: usize = 32;
+
+/// 生成 0..=max_len 个 action 组成的序列
+pub fn action_sequences<A: Debug>(
+    actions: impl Strategy<Value = A>,
+    max_len: usize,
+) -> impl Strategy<Value = Vec<A>> {
+    vec(actions, 0..=max_len)
+}
+
+/// 确定性：同样的初始 state + 同样的 action 序列，两次折叠结果必须相等
+pub fn check_determinism<S, A>(
+    states: impl Strategy<Value = S>,
+    actions: impl Strategy<Value = A>,
+    reducer: impl Fn(&S, &A) -> S,
+) where
+    S: Clone + PartialEq + Debug,
+    A: Clone + Debug,
+{
+    run(
+        (states, action_sequences(actions, MAX_SEQUENCE_LEN)),
+        |(initial, actions)| {
+            let fold = |state: S| actions.iter().fold(state, |s, a| reducer(&s, a));
+            let first = fold(initial.clone());
+            let second = fold(initial);
+            if first != second {
+                return Err(TestCaseError::fail(format!(
+                    "Reducer is not deterministic: {:?} != {:?}",
+                    first, second
+                )));
+            }
+            Ok(())
+        },
+    );
+}
+
+/// 未知 action 不改变 state（对应 Redux 的 `default: return state`）
+/// unknown 由调用方给出：例如没有被这个 slice 处理的那些 action
+pub fn check_unknown_action_identity<S, A>(
+    states: impl Strategy<Value = S>,
+    unknown: impl Strategy<Value = A>,
+    reducer: impl Fn(&S, &A) -> S,
+) where
+    S: Clone + PartialEq + Debug,
+    A: Clone + Debug,
+{
+    run((states, unknown), |(state, action)| {
+        let next = reducer(&state, &action);
+        if next != state {
+            return Err(TestCaseError::fail(format!(
+                "Unknown action {:?} changed state {:?} into {:?}",
+                action, state, next
+            )));
+        }
+        Ok(())
+    });
+}
+
+/// INIT 处理：任意 action 序列之后再收到 INIT，state 保持不变
+pub fn check_init_handling<S, A>(
+    initial: S,
+    actions: impl Strategy<Value = A>,
+    init_action: A,
+    reducer: impl Fn(&S, &A) -> S,
+) where
+    S: Clone + PartialEq + Debug,
+    A: Clone + Debug,
+{
+    run(action_sequences(actions, MAX_SEQUENCE_LEN), |actions| {
+        let state = actions
+            .iter()
+            .fold(reducer(&initial, &init_action), |s, a| reducer(&s, a));
+        let next = reducer(&state, &init_action);
+        if next != state {
+            return Err(TestCaseError::fail(format!(
+                "INIT changed state {:?} into {:?}",
+                state, next
+            )));
+        }
+        Ok(())
+    });
+}
+
+fn run<T: Strategy>(strategy: T, test: impl Fn(T::Value) -> Result<(), TestCaseError>) {
+    let mut runner = TestRunner::default();
+    if let Err(err) = runner.run(&strategy, test) {
+        panic!("{}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use proptest::strategy::Just;
+
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    enum Action {
+        Init,
+        Add(i32),
+        Other,
+    }
+
+    fn adds() -> impl Strategy<Value = Action> {
+        (-10..10i32).prop_map(Action::Add)
+    }
+
+    fn lawful(state: &i32, action: &Action) -> i32 {
+        match action {
+            Action::Add(n) => state + n,
+            Action::Init | Action::Other => *state,
+        }
+    }
+
+    // 每次调用都把一个隐藏的计数器加进 state
+    fn impure() -> impl Fn(&i32, &Action) -> i32 {
+        let calls = Cell::new(0);
+        move |state, action| {
+            calls.set(calls.get() + 1);
+            lawful(state, action) + calls.get()
+        }
+    }
+
+    #[test]
+    fn lawful_reducer_passes_every_law() {
+        check_determinism(-100..100i32, adds(), lawful);
+        check_unknown_action_identity(-100..100i32, Just(Action::Other), lawful);
+        check_init_handling(0, adds(), Action::Init, lawful);
+    }
+
+    #[test]
+    #[should_panic(expected = "Reducer is not deterministic")]
+    fn impure_reducer_fails_determinism() {
+        check_determinism(-100..100i32, adds(), impure());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown action")]
+    fn impure_reducer_fails_unknown_action_identity() {
+        check_unknown_action_identity(-100..100i32, Just(Action::Other), impure());
+    }
+
+    #[test]
+    #[should_panic(expected = "INIT changed state")]
+    fn impure_reducer_fails_init_handling() {
+        check_init_handling(0, adds(), Action::Init, impure());
+    }
+}
@@ -0,0 +1,83 @@
+use js_sys::Function;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use wasm_bindgen::prelude::*;
+
+use crate::core::store::{Store, Subscription};
+
+type JsResult<T> = Result<T, JsValue>;
+
+/// 给 JS 用的 store：Rust 侧持有 state，JS/React 侧像普通 Redux store 一样使用
+/// wasm_bindgen 不支持泛型，这里把 Store<S, A> 擦除成几个闭包
+#[wasm_bindgen]
+pub struct JsStore {
+    dispatch: Box<dyn Fn(JsValue) -> JsResult<()>>,
+    get_state: Box<dyn Fn() -> JsResult<JsValue>>,
+    subscribe: Box<dyn Fn(Function) -> Subscription>,
+}
+
+impl JsStore {
+    /// 在 Rust 侧构造好 store 后包装给 JS（通常在一个 #[wasm_bindgen] 的工厂函数里）
+    pub fn new<S, A>(store: Store<S, A>) -> Self
+    where
+        S: Serialize + Clone + 'static,
+        A: Serialize + DeserializeOwned + 'static,
+    {
+        let dispatch_store = store.clone();
+        let state_store = store.clone();
+        Self {
+            dispatch: Box::new(move |action| {
+                let action: A = serde_wasm_bindgen::from_value(action)?;
+                dispatch_store.dispatch(action);
+                Ok(())
+            }),
+            get_state: Box::new(move || to_js(&state_store.get_state())),
+            subscribe: Box::new(move |listener| {
+                store.subscribe(move |state, action| {
+                    // JS 回调抛出的异常不应打断其它 listener
+                    if let (Ok(state), Ok(action)) = (to_js(state), to_js(action)) {
+                        let _ = listener.call2(&JsValue::NULL, &state, &action);
+                    }
+                })
+            }),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl JsStore {
+    /// action 是一个可以反序列化成 A 的 JS 对象（例如 { type, payload }）
+    pub fn dispatch(&self, action: JsValue) -> JsResult<()> {
+        (self.dispatch)(action)
+    }
+
+    #[wasm_bindgen(js_name = getState)]
+    pub fn get_state(&self) -> JsResult<JsValue> {
+        (self.get_state)()
+    }
+
+    /// listener 以 (state, action) 调用；JS 侧没有 Drop，需要调用 unsubscribe() 或 free() 退订
+    pub fn subscribe(&self, listener: Function) -> JsSubscription {
+        JsSubscription {
+            inner: Some((self.subscribe)(listener)),
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct JsSubscription {
+    inner: Option<Subscription>,
+}
+
+#[wasm_bindgen]
+impl JsSubscription {
+    pub fn unsubscribe(&mut self) {
+        if let Some(subscription) = self.inner.take() {
+            subscription.unsubscribe();
+        }
+    }
+}
+
+fn to_js<T: Serialize>(value: &T) -> JsResult<JsValue> {
+    serde_wasm_bindgen::to_value(value).map_err(Into::into)
+}
@@ -0,0 +1,87 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+use crate::core::store::Store;
+
+/// 放进 ContextProvider 的 store：按“是否同一个 store”比较，state 变化不会让 context 失效
+///
+/// `<ContextProvider<StoreContext<S, A>> context={StoreContext::new(store)}>...`
+pub struct StoreContext<S, A>(pub Store<S, A>);
+
+impl<S, A> StoreContext<S, A> {
+    pub fn new(store: Store<S, A>) -> Self {
+        Self(store)
+    }
+}
+
+impl<S, A> Clone for StoreContext<S, A> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<S: 'static, A: 'static> PartialEq for StoreContext<S, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ptr_eq(&other.0)
+    }
+}
+
+/// 取得最近的 StoreContext 里的 store
+#[hook]
+pub fn use_store<S, A>() -> Store<S, A>
+where
+    S: 'static,
+    A: 'static,
+{
+    use_context::<StoreContext<S, A>>()
+        .expect("use_store() must be called inside a ContextProvider<StoreContext<S, A>>.")
+        .0
+}
+
+#[hook]
+pub fn use_dispatch<S, A>() -> Callback<A>
+where
+    S: Clone + 'static,
+    A: 'static,
+{
+    let store = use_store::<S, A>();
+    Callback::from(move |action| store.dispatch(action))
+}
+
+/// 订阅 selector 的结果：只有选出的值变化（PartialEq）时才重新渲染
+/// selector 只在挂载时捕获一次；依赖 props 的 selector 需要换一个组件 key 重新挂载
+#[hook]
+pub fn use_selector<S, A, T, F>(selector: F) -> T
+where
+    S: Clone + 'static,
+    A: 'static,
+    T: PartialEq + Clone + 'static,
+    F: Fn(&S) -> T + 'static,
+{
+    let store = use_store::<S, A>();
+    let selector = use_memo((), move |_| selector);
+    let selected = {
+        let store = store.clone();
+        let selector = selector.clone();
+        use_state(move || selector(&store.get_state()))
+    };
+
+    {
+        let selected = selected.clone();
+        use_effect_with(StoreContext::new(store), move |context| {
+            let last = Rc::new(RefCell::new((*selected).clone()));
+            let subscription = context.0.subscribe(move |state, _| {
+                let next = selector(state);
+                if *last.borrow() != next {
+                    *last.borrow_mut() = next.clone();
+                    selected.set(next);
+                }
+            });
+            move || drop(subscription)
+        });
+    }
+
+    (*selected).clone()
+}